@@ -1,4 +1,510 @@
-use crate::{errors::NetworkError, Parser};
+use crate::errors::{ApiKeyError, SpacepixError, TleError};
+use crate::orbit::Orbit;
+use crate::Parser;
+use std::fmt::Display;
+use std::hash::{BuildHasher, Hasher};
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, OnceLock, RwLock};
+use std::time::{Duration, Instant};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// Timeouts and retries for every request Spacepix makes, edited in Settings->Network
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct HttpSettings {
+    pub connect_timeout_ms: u64,
+    // Blocking reqwest only has a whole request timeout, which covers reading the body
+    pub read_timeout_ms: u64,
+    pub max_retries: u32,
+    pub initial_backoff_ms: u64,
+    pub max_backoff_ms: u64,
+    // Fetches run on the UI thread, so all the attempts and waits together get no longer than this
+    pub total_timeout_ms: u64,
+    pub proxy: ProxySettings,
+}
+
+impl Default for HttpSettings {
+    fn default() -> Self {
+        Self {
+            connect_timeout_ms: 5_000,
+            read_timeout_ms: 15_000,
+            max_retries: 2,
+            initial_backoff_ms: 500,
+            max_backoff_ms: 4_000,
+            total_timeout_ms: 20_000,
+            proxy: ProxySettings::default(),
+        }
+    }
+}
+
+impl HttpSettings {
+    // Doubles with each retry up to the maximum, then jitter in 0..=1 picks somewhere in the top
+    // half so clients that failed together don't all come back at once
+    pub fn backoff(&self, retry: u32, jitter: f64) -> Duration {
+        let delay = self
+            .initial_backoff_ms
+            .saturating_mul(2u64.saturating_pow(retry))
+            .min(self.max_backoff_ms) as f64;
+        Duration::from_millis((delay / 2.0 + delay / 2.0 * jitter.clamp(0.0, 1.0)) as u64)
+    }
+}
+
+/// Outbound proxies and extra root certificates, for networks that only reach out through a
+/// proxy or inspect TLS. Empty fields aren't used, with no proxies set reqwest falls back to the
+/// HTTP_PROXY, HTTPS_PROXY, ALL_PROXY and NO_PROXY environment variables
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct ProxySettings {
+    pub http_proxy: String,
+    pub https_proxy: String,
+    pub socks_proxy: String, // socks5:// or socks5h://, used for anything the others don't cover
+    pub no_proxy: String,    // Comma separated hosts, domains and IP ranges that skip the proxies
+    pub ca_certificates: Vec<PathBuf>,
+}
+
+impl ProxySettings {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    // The command line flags win over the saved settings, one field at a time
+    pub fn overridden_by(&self, other: &ProxySettings) -> Self {
+        let pick = |saved: &String, flag: &String| match flag.trim().is_empty() {
+            true => saved.clone(),
+            false => flag.clone(),
+        };
+        Self {
+            http_proxy: pick(&self.http_proxy, &other.http_proxy),
+            https_proxy: pick(&self.https_proxy, &other.https_proxy),
+            socks_proxy: pick(&self.socks_proxy, &other.socks_proxy),
+            no_proxy: pick(&self.no_proxy, &other.no_proxy),
+            ca_certificates: match other.ca_certificates.is_empty() {
+                true => self.ca_certificates.clone(),
+                false => other.ca_certificates.clone(),
+            },
+        }
+    }
+
+    // reqwest uses the first proxy that matches, so the catch all SOCKS proxy goes last
+    pub fn proxies(&self) -> Result<Vec<reqwest::Proxy>, SpacepixError> {
+        let no_proxy = reqwest::NoProxy::from_string(self.no_proxy.trim());
+        let mut proxies = Vec::new();
+        for (url, scheme) in [
+            (&self.http_proxy, "http"),
+            (&self.https_proxy, "https"),
+            (&self.socks_proxy, "all"),
+        ] {
+            let url = url.trim();
+            if url.is_empty() {
+                continue;
+            }
+            let proxy = match scheme {
+                "http" => reqwest::Proxy::http(url),
+                "https" => reqwest::Proxy::https(url),
+                _ => reqwest::Proxy::all(url),
+            };
+            match proxy {
+                Ok(proxy) => proxies.push(proxy.no_proxy(no_proxy.clone())),
+                Err(e) => {
+                    return Err(SpacepixError::Proxy {
+                        url: url.to_string(),
+                        source: e,
+                    })
+                }
+            }
+        }
+        Ok(proxies)
+    }
+
+    // Each file can be a PEM bundle or a single DER certificate
+    pub fn certificates(&self) -> Result<Vec<reqwest::Certificate>, SpacepixError> {
+        let mut certificates = Vec::new();
+        for path in &self.ca_certificates {
+            let bytes = fs::read(path).map_err(|e| SpacepixError::CertificateFile {
+                path: path.clone(),
+                source: e,
+            })?;
+            let parsed = match bytes.starts_with(b"-----BEGIN") {
+                true => reqwest::Certificate::from_pem_bundle(&bytes),
+                false => reqwest::Certificate::from_der(&bytes).map(|certificate| vec![certificate]),
+            };
+            match parsed {
+                Ok(parsed) if !parsed.is_empty() => certificates.extend(parsed),
+                Ok(_) => {
+                    return Err(SpacepixError::Certificate {
+                        path: path.clone(),
+                        source: None,
+                    })
+                }
+                Err(e) => {
+                    return Err(SpacepixError::Certificate {
+                        path: path.clone(),
+                        source: Some(e),
+                    })
+                }
+            }
+        }
+        Ok(certificates)
+    }
+}
+
+/// A reqwest client built from HttpSettings, retrying server errors and failed connections
+#[derive(Debug, Clone)]
+pub struct HttpClient {
+    client: reqwest::blocking::Client,
+    settings: HttpSettings,
+}
+
+impl HttpClient {
+    pub fn new(settings: HttpSettings) -> Result<Self, SpacepixError> {
+        let mut builder = reqwest::blocking::Client::builder()
+            .connect_timeout(Duration::from_millis(settings.connect_timeout_ms))
+            .timeout(Duration::from_millis(settings.read_timeout_ms));
+        for proxy in settings.proxy.proxies()? {
+            builder = builder.proxy(proxy);
+        }
+        for certificate in settings.proxy.certificates()? {
+            builder = builder.add_root_certificate(certificate);
+        }
+        let client = builder.build().map_err(SpacepixError::Client)?;
+        Ok(Self { client, settings })
+    }
+
+    pub fn settings(&self) -> &HttpSettings {
+        &self.settings
+    }
+
+    // 4xx responses come straight back, asking again won't change the answer. Nothing is retried
+    // once the wait would run past total_timeout_ms
+    pub fn get_blocking(&self, url: &str) -> Result<reqwest::blocking::Response, SpacepixError> {
+        let _span = tracing::info_span!("request", url = %redact_api_key(url)).entered();
+        let deadline = Instant::now() + Duration::from_millis(self.settings.total_timeout_ms);
+        let mut retry = 0;
+        loop {
+            let started = Instant::now();
+            let timeout = deadline
+                .saturating_duration_since(started)
+                .min(Duration::from_millis(self.settings.read_timeout_ms));
+            let result = self.client.get(url).timeout(timeout).send();
+            let retryable = match &result {
+                Ok(response) => response.status().is_server_error(),
+                Err(e) => e.is_timeout() || e.is_connect() || e.is_request(),
+            };
+            let backoff = self.settings.backoff(retry, jitter());
+            let out_of_time = Instant::now() + backoff >= deadline;
+            if !retryable || retry >= self.settings.max_retries || out_of_time {
+                let mut record = RequestRecord::new(url, started.elapsed(), retry);
+                return match result {
+                    Ok(mut response) => {
+                        record.status = Some(response.status().as_u16());
+                        record.bytes = response.content_length();
+                        record.cache = cache_status(response.headers());
+                        record.remaining = header_value(response.headers(), "x-ratelimit-remaining");
+                        // Whoever reads the body adds it to the record through this
+                        let id = log_request(record);
+                        response.extensions_mut().insert(RequestId(id));
+                        Ok(response)
+                    }
+                    Err(e) => {
+                        // reqwest puts the whole URL in its errors
+                        record.error = Some(redact_api_key(&e.to_string()));
+                        log_request(record);
+                        Err(e.into())
+                    }
+                };
+            }
+            // The request span has the URL with the key redacted
+            tracing::debug!("Retrying in {:?}, attempt {} failed", backoff, retry + 1);
+            std::thread::sleep(backoff);
+            retry += 1;
+        }
+    }
+}
+
+/// A request the shared client made, listed in Help->Network Inspector
+#[derive(Debug, Clone)]
+pub struct RequestRecord {
+    pub id: u64,
+    pub time: chrono::DateTime<chrono::Local>,
+    pub url: String, // With the API key redacted
+    pub status: Option<u16>, // None when no response came back
+    pub error: Option<String>,
+    pub latency: Duration, // Until the headers of the last attempt arrived
+    pub retries: u32,
+    pub bytes: Option<u64>,
+    pub cache: Option<bool>, // Whether the CDN in front of the API answered from its cache
+    pub remaining: Option<u32>,
+    pub body: Option<Arc<str>>, // Text responses only, downloads just keep their size
+}
+
+impl RequestRecord {
+    fn new(url: &str, latency: Duration, retries: u32) -> Self {
+        Self {
+            id: 0,
+            time: chrono::Local::now(),
+            url: redact_api_key(url),
+            status: None,
+            error: None,
+            latency,
+            retries,
+            bytes: None,
+            cache: None,
+            remaining: None,
+            body: None,
+        }
+    }
+}
+
+// Tags a response with its record so the body can be added once it has been read
+#[derive(Debug, Clone, Copy)]
+struct RequestId(u64);
+
+const REQUEST_LOG_LEN: usize = 200;
+const REQUEST_BODY_LIMIT: usize = 512 * 1024;
+
+fn request_log_store() -> &'static Mutex<VecDeque<RequestRecord>> {
+    static LOG: OnceLock<Mutex<VecDeque<RequestRecord>>> = OnceLock::new();
+    LOG.get_or_init(|| Mutex::new(VecDeque::with_capacity(REQUEST_LOG_LEN)))
+}
+
+fn with_request_log<R>(f: impl FnOnce(&mut VecDeque<RequestRecord>) -> R) -> R {
+    match request_log_store().lock() {
+        Ok(mut log) => f(&mut log),
+        Err(poisoned) => f(&mut poisoned.into_inner()),
+    }
+}
+
+fn log_request(mut record: RequestRecord) -> u64 {
+    static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+    record.id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+    tracing::debug!(status = ?record.status, latency = ?record.latency, retries = record.retries, "Response");
+    let id = record.id;
+    with_request_log(|log| {
+        if log.len() == REQUEST_LOG_LEN {
+            log.pop_front();
+        }
+        log.push_back(record);
+    });
+    id
+}
+
+// Fill in the size, and the text when there is some, once a response's body has been read
+fn log_response_body(id: Option<RequestId>, bytes: usize, body: Option<&str>) {
+    let Some(RequestId(id)) = id else {
+        return;
+    };
+    let body = body.map(|body| match body.len() > REQUEST_BODY_LIMIT {
+        true => {
+            let mut end = REQUEST_BODY_LIMIT;
+            while !body.is_char_boundary(end) {
+                end -= 1;
+            }
+            Arc::from(format!("{}\n[{} more bytes]", &body[..end], body.len() - end))
+        }
+        false => Arc::from(body),
+    });
+    with_request_log(|log| {
+        if let Some(record) = log.iter_mut().rev().find(|record| record.id == id) {
+            record.bytes = Some(bytes as u64);
+            record.body = body;
+        }
+    });
+}
+
+// Oldest first, at most the last REQUEST_LOG_LEN requests
+pub fn request_log() -> Vec<RequestRecord> {
+    with_request_log(|log| log.iter().cloned().collect())
+}
+
+pub fn clear_request_log() {
+    with_request_log(|log| log.clear());
+}
+
+// Keys must never end up on screen or in a bug report. Works on error messages too, reqwest puts
+// the whole URL in them
+pub fn redact_api_key(text: &str) -> String {
+    let mut redacted = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find("api_key=") {
+        let start = start + "api_key=".len();
+        let key = &rest[start..];
+        let end = key
+            .find(|c: char| !(c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '%'))
+            .unwrap_or(key.len());
+        redacted.push_str(&rest[..start]);
+        redacted.push_str("REDACTED");
+        rest = &key[end..];
+    }
+    redacted.push_str(rest);
+    redacted
+}
+
+fn header_value<T: std::str::FromStr>(headers: &reqwest::header::HeaderMap, name: &str) -> Option<T> {
+    headers
+        .get(name)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.trim().parse().ok())
+}
+
+// Spacepix has no cache of its own, but CloudFront and Cloudflare say whether they answered
+fn cache_status(headers: &reqwest::header::HeaderMap) -> Option<bool> {
+    let status = header_value::<String>(headers, "x-cache")
+        .or_else(|| header_value(headers, "cf-cache-status"))?
+        .to_lowercase();
+    match (status.contains("hit"), status.contains("miss")) {
+        (true, _) => Some(true),
+        (_, true) => Some(false),
+        _ => None,
+    }
+}
+
+// A number in 0..=1 from the standard library's randomly seeded hasher, good enough for jitter
+fn jitter() -> f64 {
+    std::collections::hash_map::RandomState::new().build_hasher().finish() as f64 / u64::MAX as f64
+}
+
+// Every fetcher shares one client, so connections are reused and the settings apply everywhere
+fn shared_client() -> &'static RwLock<HttpClient> {
+    static CLIENT: OnceLock<RwLock<HttpClient>> = OnceLock::new();
+    CLIENT.get_or_init(|| {
+        RwLock::new(HttpClient::new(HttpSettings::default()).expect("Failed to build the HTTP client"))
+    })
+}
+
+pub fn http_client() -> HttpClient {
+    match shared_client().read() {
+        Ok(client) => client.clone(),
+        Err(poisoned) => poisoned.into_inner().clone(),
+    }
+}
+
+// Rebuild the shared client, the old one stays in use if the settings don't work
+pub fn configure_http_client(settings: &HttpSettings) -> Result<(), SpacepixError> {
+    let client = HttpClient::new(settings.clone())?;
+    match shared_client().write() {
+        Ok(mut shared) => *shared = client,
+        Err(poisoned) => *poisoned.into_inner() = client,
+    }
+    Ok(())
+}
+
+// Fetch a url and return the body as text
+fn get_text_blocking(url: &str) -> Result<String, SpacepixError> {
+    // .replace to get rid of the extra quotes from the URL
+    read_text(http_client().get_blocking(&url.replace("\"", ""))?)
+}
+
+// The body of a response, or why the request was turned down
+fn read_text(response: reqwest::blocking::Response) -> Result<String, SpacepixError> {
+    let id = response.extensions().get::<RequestId>().copied();
+    let status = response.status().as_u16();
+    let status_error = response.error_for_status_ref().err();
+    let body = response.text()?;
+    log_response_body(id, body.len(), Some(&body));
+    if let Some(e) = api_key_error(status, &body) {
+        return Err(SpacepixError::ApiKey(e));
+    }
+    match status_error {
+        Some(e) => Err(e.into()),
+        None => Ok(body),
+    }
+}
+
+// api.nasa.gov answers a bad or missing key with a 403 and an error code in the body
+fn api_key_error(status: u16, body: &str) -> Option<ApiKeyError> {
+    match status == 403 && (body.contains("API_KEY_INVALID") || body.contains("API_KEY_MISSING")) {
+        true => Some(ApiKeyError::InvalidApiKey()),
+        false => None,
+    }
+}
+
+// Check that NASA accepts a key, using about the smallest response the API has
+#[tracing::instrument(skip_all, fields(profile = parser.profile()))]
+pub fn validate_api_key_blocking(parser: &Parser, key: &str) -> Result<(), ApiKeyError> {
+    match get_text_blocking(&parser.key_check_url(key)) {
+        Ok(_) => Ok(()),
+        Err(SpacepixError::ApiKey(e)) => Err(e),
+        // Out of requests for the hour, but the key itself is fine
+        Err(SpacepixError::Status { status: 429, .. }) => Ok(()),
+        Err(e) => Err(ApiKeyError::Unreachable(e.to_string())),
+    }
+}
+
+// Fetch a NASA API url and parse the body as JSON, counting it against the key's rate limit
+fn get_json_blocking(parser: &Parser, url: &str) -> Result<json::JsonValue, SpacepixError> {
+    let response = http_client().get_blocking(&url.replace("\"", ""))?;
+    if url.contains("api_key=") {
+        parser.record_request(response.headers());
+    }
+    json::parse(&read_text(response)?).map_err(SpacepixError::Parse)
+}
+
+// Images and other binary files, counted against the key like get_json_blocking()
+fn get_bytes_blocking(parser: &Parser, url: &str) -> Result<Vec<u8>, SpacepixError> {
+    let response = http_client().get_blocking(url)?;
+    if url.contains("api_key=") {
+        parser.record_request(response.headers());
+    }
+    let id = response.extensions().get::<RequestId>().copied();
+    let bytes = response.error_for_status().and_then(|r| r.bytes())?;
+    log_response_body(id, bytes.len(), None);
+    Ok(bytes.to_vec())
+}
+
+/// Requests made with one key this run, and what api.nasa.gov last said was left of the hour
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct RateLimit {
+    pub requests: u32,
+    pub limit: Option<u32>,
+    pub remaining: Option<u32>,
+}
+
+impl RateLimit {
+    pub fn record(&mut self, headers: &reqwest::header::HeaderMap) {
+        self.requests += 1;
+        self.limit = header_value(headers, "x-ratelimit-limit").or(self.limit);
+        self.remaining = header_value(headers, "x-ratelimit-remaining").or(self.remaining);
+    }
+}
+
+// JPL's SSD APIs send most numbers as strings
+fn parse_f64(value: &json::JsonValue) -> Option<f64> {
+    value
+        .as_f64()
+        .or_else(|| value.as_str().and_then(|value| value.trim().parse().ok()))
+}
+
+// Where downloaded images end up, ~/Pictures/Spacepix or the working directory if there is no home
+pub fn default_download_dir() -> PathBuf {
+    match std::env::var_os("HOME").or_else(|| std::env::var_os("USERPROFILE")) {
+        Some(home) => Path::new(&home).join("Pictures").join("Spacepix"),
+        None => PathBuf::from("."),
+    }
+}
+
+// Download a file into dir, keeping the file name from the url, and return where it was written
+#[tracing::instrument(skip_all, fields(url = %redact_api_key(url), dir = %dir.display()))]
+pub fn save_file_blocking(url: &str, dir: &Path) -> Result<PathBuf, SpacepixError> {
+    let file_name = url
+        .split(['?', '#'])
+        .next()
+        .and_then(|path| path.rsplit('/').next())
+        .filter(|name| !name.is_empty())
+        .unwrap_or("spacepix_download")
+        .replace("%20", " ");
+    let response = http_client().get_blocking(url)?;
+    let id = response.extensions().get::<RequestId>().copied();
+    let bytes = response.error_for_status().and_then(|r| r.bytes())?;
+    log_response_body(id, bytes.len(), None);
+    fs::create_dir_all(dir)?;
+    let path = dir.join(file_name);
+    fs::write(&path, bytes)?;
+    Ok(path)
+}
 
 #[derive(Default, Clone, serde::Deserialize, serde::Serialize)]
 #[serde(default)] // if we add new fields, give them default values when deserializing old state
@@ -64,23 +570,19 @@ impl Apod {
         }
     }
 
-    pub fn get_apod_data_blocking() -> Result<Self, NetworkError> {
-        match reqwest::blocking::get(Parser::default().apod_url().replace("\"", "")) { // .replace to get rid of the extra quotes from the URL
-            Ok(r) => match json::parse(r.text().unwrap().as_str()) {
-                Ok(json_obj) => Ok(Self {
-                    copyright: json_obj["copyright"].to_string(),
-                    date: json_obj["date"].to_string(),
-                    explanation: json_obj["explanation"].to_string(),
-                    hdurl: json_obj["hdurl"].to_string(),
-                    media_type: json_obj["media_type"].to_string(),
-                    service_version: json_obj["service_version"].to_string(),
-                    title: json_obj["title"].to_string(),
-                    url: json_obj["url"].to_string(),
-                }),
-                Err(e) => return Err(NetworkError::JsonParseFailed(e)),
-            },
-            Err(e) => return Err(NetworkError::ConnectionFailed(e)),
-        }
+    #[tracing::instrument(skip_all, fields(profile = parser.profile()))]
+    pub fn get_apod_data_blocking(parser: &Parser) -> Result<Self, SpacepixError> {
+        let json_obj = get_json_blocking(parser, &parser.apod_url())?;
+        Ok(Self {
+            copyright: json_obj["copyright"].to_string(),
+            date: json_obj["date"].to_string(),
+            explanation: json_obj["explanation"].to_string(),
+            hdurl: json_obj["hdurl"].to_string(),
+            media_type: json_obj["media_type"].to_string(),
+            service_version: json_obj["service_version"].to_string(),
+            title: json_obj["title"].to_string(),
+            url: json_obj["url"].to_string(),
+        })
     }
 }
 #[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
@@ -116,13 +618,28 @@ impl Default for Links {
  * relative_velocity tuple key (kilometers_per_second, kilometers_per_hour, miles_per_hour)
  * miss_distance tuple key (astronomical, lunar, kilometers, miles)
  */
+// Saved app state from before CAD results has a plain bool for the hazard flag
+fn hazard_flag<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<Option<bool>, D::Error> {
+    #[derive(serde::Deserialize)]
+    #[serde(untagged)]
+    enum HazardFlag {
+        Flag(bool),
+        Classified(Option<bool>),
+    }
+    Ok(match serde::Deserialize::deserialize(deserializer)? {
+        HazardFlag::Flag(flag) => Some(flag),
+        HazardFlag::Classified(flag) => flag,
+    })
+}
+
 #[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
 pub struct NearEarthObject {
     pub id: String,
     pub neo_reference_id: String,
     pub name: String,
     pub estimated_diameter: ((f32, f32), (f32, f32)), // ((feet_min, feet_max), (meters_min, meters_max))
-    pub is_potentially_hazardous_asteroid: bool,
+    #[serde(deserialize_with = "hazard_flag")]
+    pub is_potentially_hazardous_asteroid: Option<bool>, // None for CAD results, CAD doesn't classify them
     pub close_approach_date: String,
     pub close_approach_date_full: String,
     pub epoch_date_close_approach: u64,
@@ -138,7 +655,7 @@ impl NearEarthObject {
         neo_reference_id: String,
         name: String,
         estimated_diameter: ((f32, f32), (f32, f32)),
-        is_potentially_hazardous_asteroid: bool,
+        is_potentially_hazardous_asteroid: Option<bool>,
         close_approach_date: String,
         close_approach_date_full: String,
         epoch_date_close_approach: u64,
@@ -167,14 +684,14 @@ impl NearEarthObject {
 #[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
 pub struct NEOFeed {
     pub links: Links,
-    pub element_count: u8,
+    pub element_count: u32,
     pub near_earth_objects: Vec<NearEarthObject>,
 }
 
 impl NEOFeed {
     pub fn new(
         links: Links,
-        element_count: u8,
+        element_count: u32,
         near_earth_objects: Vec<NearEarthObject>
     ) -> Self {
         Self {
@@ -185,88 +702,80 @@ impl NEOFeed {
     }
 
     // Retrieve a list of Near Earth Objects from NASA and return a result with the list or error
-    pub fn get_neows_feed_blocking(&mut self, date: &str) -> Result<&mut NEOFeed, NetworkError> {
-        match reqwest::blocking::get(Parser::default().neows_url(date).replace("\"", "")) {
-            Ok(r) => match json::parse(r.text().unwrap().as_str()) {
-                Ok(json_obj) => {
-                    // dbg!("{:?}", &json_obj);
-                    let neo_objects_json = json_obj["near_earth_objects"][date].members();
-                    let mut neo_vec: Vec<NearEarthObject> = Vec::default();
-                    for object in neo_objects_json {
-                        let neo = NearEarthObject::new(
-                            object["id"].to_string(),
-                            object["neo_reference_id"].to_string(),
-                            object["name"].to_string(),
-                            (
-                                (
-                                    object["estimated_diameter"]["feet"]["estimated_diameter_min"]
-                                        .as_f32()
-                                        .unwrap(),
-                                    object["estimated_diameter"]["feet"]["estimated_diameter_max"]
-                                        .as_f32()
-                                        .unwrap(),
-                                ),
-                                (
-                                    object["estimated_diameter"]["meters"]
-                                        ["estimated_diameter_min"]
-                                        .as_f32()
-                                        .unwrap(),
-                                    object["estimated_diameter"]["meters"]
-                                        ["estimated_diameter_max"]
-                                        .as_f32()
-                                        .unwrap(),
-                                ),
-                            ), // ((feet_min, feet_max), (meters_min, meters_max))
-                            object["is_potentially_hazardous_asteroid"]
-                                .as_bool()
-                                .unwrap(),
-                            object["close_approach_data"][0]["close_approach_date"].to_string(),
-                            object["close_approach_data"][0]["close_approach_date_full"]
-                                .to_string(),
-                            object["close_approach_data"][0]["epoch_date_close_approach"]
-                                .as_u64()
-                                .unwrap(),
-                            (
-                                object["close_approach_data"][0]["relative_velocity"]
-                                    ["kilometers_per_second"]
-                                    .to_string(),
-                                object["close_approach_data"][0]["relative_velocity"]
-                                    ["kilometers_per_hour"]
-                                    .to_string(),
-                                object["close_approach_data"][0]["relative_velocity"]
-                                    ["miles_per_hour"]
-                                    .to_string(),
-                            ), // (kilometers_per_second, kilometers_per_hour, miles_per_hour)
-                            (
-                                object["close_approach_data"][0]["miss_distance"]["astronomical"]
-                                    .to_string(),
-                                object["close_approach_data"][0]["miss_distance"]["lunar"]
-                                    .to_string(),
-                                object["close_approach_data"][0]["miss_distance"]["kilometers"]
-                                    .to_string(),
-                                object["close_approach_data"][0]["miss_distance"]["miles"]
-                                    .to_string(),
-                            ), // (astronomical, lunar, kilometers, miles)
-                            object["close_approach_data"][0]["orbiting_body"].to_string(),
-                            object["is_sentry_object"].as_bool().unwrap(),
-                        );
-                        neo_vec.push(neo);
-                    }
-
-                    let links = Links::new(
-                        json_obj["links"]["next"].to_string(),
-                        json_obj["links"]["previous"].to_string(),
-                        json_obj["links"]["self"].to_string(),
-                    );
-                    
-                    self.links = links;
-                    self.near_earth_objects = neo_vec;
-                    Ok(self)
-                }
-                Err(e) => return Err(NetworkError::JsonParseFailed(e)),
-            },
-            Err(e) => return Err(NetworkError::ConnectionFailed(e)),
+    #[tracing::instrument(skip_all, fields(date))]
+    pub fn get_neows_feed_blocking(&mut self, parser: &Parser, date: &str) -> Result<&mut NEOFeed, SpacepixError> {
+        let json_obj = get_json_blocking(parser, &parser.neows_url(date))?;
+        let neo_objects_json = json_obj["near_earth_objects"][date].members();
+        let mut neo_vec: Vec<NearEarthObject> = Vec::default();
+        // Missing sizes or dates show as zero rather than taking the whole feed down
+        for object in neo_objects_json {
+            let neo = NearEarthObject::new(
+                object["id"].to_string(),
+                object["neo_reference_id"].to_string(),
+                object["name"].to_string(),
+                (
+                    (
+                        object["estimated_diameter"]["feet"]["estimated_diameter_min"]
+                            .as_f32()
+                            .unwrap_or_default(),
+                        object["estimated_diameter"]["feet"]["estimated_diameter_max"]
+                            .as_f32()
+                            .unwrap_or_default(),
+                    ),
+                    (
+                        object["estimated_diameter"]["meters"]
+                            ["estimated_diameter_min"]
+                            .as_f32()
+                            .unwrap_or_default(),
+                        object["estimated_diameter"]["meters"]
+                            ["estimated_diameter_max"]
+                            .as_f32()
+                            .unwrap_or_default(),
+                    ),
+                ), // ((feet_min, feet_max), (meters_min, meters_max))
+                object["is_potentially_hazardous_asteroid"].as_bool(),
+                object["close_approach_data"][0]["close_approach_date"].to_string(),
+                object["close_approach_data"][0]["close_approach_date_full"]
+                    .to_string(),
+                object["close_approach_data"][0]["epoch_date_close_approach"]
+                    .as_u64()
+                    .unwrap_or_default(),
+                (
+                    object["close_approach_data"][0]["relative_velocity"]
+                        ["kilometers_per_second"]
+                        .to_string(),
+                    object["close_approach_data"][0]["relative_velocity"]
+                        ["kilometers_per_hour"]
+                        .to_string(),
+                    object["close_approach_data"][0]["relative_velocity"]
+                        ["miles_per_hour"]
+                        .to_string(),
+                ), // (kilometers_per_second, kilometers_per_hour, miles_per_hour)
+                (
+                    object["close_approach_data"][0]["miss_distance"]["astronomical"]
+                        .to_string(),
+                    object["close_approach_data"][0]["miss_distance"]["lunar"]
+                        .to_string(),
+                    object["close_approach_data"][0]["miss_distance"]["kilometers"]
+                        .to_string(),
+                    object["close_approach_data"][0]["miss_distance"]["miles"]
+                        .to_string(),
+                ), // (astronomical, lunar, kilometers, miles)
+                object["close_approach_data"][0]["orbiting_body"].to_string(),
+                object["is_sentry_object"].as_bool().unwrap_or_default(),
+            );
+            neo_vec.push(neo);
         }
+
+        let links = Links::new(
+            json_obj["links"]["next"].to_string(),
+            json_obj["links"]["previous"].to_string(),
+            json_obj["links"]["self"].to_string(),
+        );
+        
+        self.links = links;
+        self.near_earth_objects = neo_vec;
+        Ok(self)
     }
 }
 
@@ -274,30 +783,2530 @@ impl Default for NEOFeed {
     fn default() -> Self {
         Self {
             links: Links::default(),
-            element_count: u8::default(),
+            element_count: u32::default(),
             near_earth_objects: Vec::default()
         }
     }
 }
 
-#[derive(Clone, serde::Serialize, serde::Deserialize)]
+/// A CME as it was fed into a WSA-Enlil model run
+#[derive(Debug, Clone, Default, serde::Deserialize, serde::Serialize)]
+pub struct EnlilCmeInput {
+    pub cme_id: String,
+    pub cme_start_time: String,
+    pub latitude: f32,
+    pub longitude: f32,
+    pub speed: f32,
+    pub half_angle: f32,
+    pub is_most_accurate: bool,
+}
+
+impl EnlilCmeInput {
+    fn from_json(object: &json::JsonValue) -> Self {
+        Self {
+            cme_id: object["cmeid"].as_str().unwrap_or_default().to_string(),
+            cme_start_time: object["cmeStartTime"].as_str().unwrap_or_default().to_string(),
+            latitude: object["latitude"].as_f32().unwrap_or_default(),
+            longitude: object["longitude"].as_f32().unwrap_or_default(),
+            speed: object["speed"].as_f32().unwrap_or_default(),
+            half_angle: object["halfAngle"].as_f32().unwrap_or_default(),
+            is_most_accurate: object["isMostAccurate"].as_bool().unwrap_or_default(),
+        }
+    }
+}
+
+/// Predicted shock arrival at a single target (Earth, a spacecraft or a planet)
+#[derive(Debug, Clone, Default, serde::Deserialize, serde::Serialize)]
+pub struct EnlilImpact {
+    pub location: String,
+    pub arrival_time: String,
+    pub is_glancing_blow: bool,
+}
+
+/**
+ * Representative of a single WSA-Enlil model run from DONKI
+ * The Earth prediction is reported separately from the impact list by the API,
+ * use targets() to get every prediction at once.
+ */
+#[derive(Debug, Clone, Default, serde::Deserialize, serde::Serialize)]
+pub struct WSAEnlilSimulation {
+    pub simulation_id: String,
+    pub model_completion_time: String,
+    pub au: f32,
+    pub cme_inputs: Vec<EnlilCmeInput>,
+    pub estimated_shock_arrival_time: Option<String>,
+    pub estimated_duration: Option<f32>,
+    pub is_earth_glancing_blow: bool,
+    pub kp: (Option<f32>, Option<f32>, Option<f32>, Option<f32>), // (kp_18, kp_90, kp_135, kp_180)
+    pub impact_list: Vec<EnlilImpact>,
+    pub link: String,
+}
+
+impl WSAEnlilSimulation {
+    fn from_json(object: &json::JsonValue) -> Self {
+        Self {
+            simulation_id: object["simulationID"].as_str().unwrap_or_default().to_string(),
+            model_completion_time: object["modelCompletionTime"]
+                .as_str()
+                .unwrap_or_default()
+                .to_string(),
+            au: object["au"].as_f32().unwrap_or_default(),
+            cme_inputs: object["cmeInputs"]
+                .members()
+                .map(EnlilCmeInput::from_json)
+                .collect(),
+            estimated_shock_arrival_time: object["estimatedShockArrivalTime"]
+                .as_str()
+                .map(String::from),
+            estimated_duration: object["estimatedDuration"].as_f32(),
+            is_earth_glancing_blow: object["isEarthGB"].as_bool().unwrap_or_default(),
+            kp: (
+                object["kp_18"].as_f32(),
+                object["kp_90"].as_f32(),
+                object["kp_135"].as_f32(),
+                object["kp_180"].as_f32(),
+            ), // (kp_18, kp_90, kp_135, kp_180)
+            impact_list: object["impactList"]
+                .members()
+                .map(|impact| EnlilImpact {
+                    location: impact["location"].as_str().unwrap_or_default().to_string(),
+                    arrival_time: impact["arrivalTime"].as_str().unwrap_or_default().to_string(),
+                    is_glancing_blow: impact["isGlancingBlow"].as_bool().unwrap_or_default(),
+                })
+                .collect(),
+            link: object["link"].as_str().unwrap_or_default().to_string(),
+        }
+    }
+
+    // Every impact prediction of this run, Earth first if the model predicts an Earth arrival
+    pub fn targets(&self) -> Vec<EnlilImpact> {
+        let mut targets = Vec::default();
+        if let Some(arrival_time) = &self.estimated_shock_arrival_time {
+            targets.push(EnlilImpact {
+                location: String::from("Earth"),
+                arrival_time: arrival_time.clone(),
+                is_glancing_blow: self.is_earth_glancing_blow,
+            });
+        }
+        targets.extend(self.impact_list.iter().cloned());
+        targets
+    }
+
+    pub fn models_cme(&self, cme_id: &str) -> bool {
+        self.cme_inputs.iter().any(|input| input.cme_id == cme_id)
+    }
+}
+
+#[derive(Debug, Clone, Default, serde::Deserialize, serde::Serialize)]
+pub struct WSAEnlil {
+    pub simulations: Vec<WSAEnlilSimulation>,
+}
+
+impl WSAEnlil {
+    // Retrieve the WSA-Enlil runs completed between two dates (YYYY-MM-DD)
+    #[tracing::instrument(skip_all, fields(start_date, end_date))]
+    pub fn get_simulations_blocking(
+        parser: &Parser,
+        start_date: &str,
+        end_date: &str,
+    ) -> Result<Self, SpacepixError> {
+        let json_obj = get_json_blocking(parser, &parser.wsa_enlil_url(start_date, end_date))?;
+        Ok(Self {
+            simulations: json_obj
+                .members()
+                .map(WSAEnlilSimulation::from_json)
+                .collect(),
+        })
+    }
+
+    // Cross-reference a DONKI CME (e.g. "2024-01-01T00:00:00-CME-001") with the runs that modeled it
+    pub fn simulations_for_cme(&self, cme_id: &str) -> Vec<&WSAEnlilSimulation> {
+        self.simulations
+            .iter()
+            .filter(|simulation| simulation.models_cme(cme_id))
+            .collect()
+    }
+}
+
+/// Search parameters for the NASA Image and Video Library, empty fields are left out of the query
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct NIVLSearch {
+    pub q: String,
+    pub media_type: String, // Comma separated list of "image", "video" and "audio"
+    pub year_start: String,
+    pub year_end: String,
+    pub center: String,
+    pub keywords: String, // Comma separated
+    pub album: String, // Browse a curated album such as "Apollo-at-50" instead of searching
+    pub page: u32,
+    pub page_size: u32,
+}
+
+impl Default for NIVLSearch {
+    fn default() -> Self {
+        Self {
+            q: String::default(),
+            media_type: String::default(),
+            year_start: String::default(),
+            year_end: String::default(),
+            center: String::default(),
+            keywords: String::default(),
+            album: String::default(),
+            page: 1,
+            page_size: 24,
+        }
+    }
+}
+
+impl NIVLSearch {
+    // Albums are always served 100 items per page
+    pub fn album(album: &str) -> Self {
+        Self {
+            album: album.trim().to_string(),
+            page_size: 100,
+            ..Default::default()
+        }
+    }
+
+    // The non-empty query parameters in the order the API documents them
+    pub fn params(&self) -> Vec<(&str, String)> {
+        let mut params: Vec<(&str, String)> = [
+            ("q", &self.q),
+            ("media_type", &self.media_type),
+            ("year_start", &self.year_start),
+            ("year_end", &self.year_end),
+            ("center", &self.center),
+            ("keywords", &self.keywords),
+        ]
+        .into_iter()
+        .filter(|(_, value)| !value.trim().is_empty())
+        .map(|(name, value)| (name, value.trim().to_string()))
+        .collect();
+        params.push(("page", self.page.to_string()));
+        params.push(("page_size", self.page_size.to_string()));
+        params
+    }
+}
+
+/// A single search result from images-api.nasa.gov
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct NIVLItem {
+    pub nasa_id: String,
+    pub title: String,
+    pub description: String,
+    pub date_created: String,
+    pub media_type: String,
+    pub center: String,
+    pub keywords: Vec<String>,
+    pub photographer: String,
+    pub thumbnail: Option<String>,
+    pub href: String, // Collection manifest listing every file of this asset
+}
+
+impl NIVLItem {
+    fn from_json(object: &json::JsonValue) -> Self {
+        let data = &object["data"][0];
+        Self {
+            nasa_id: data["nasa_id"].as_str().unwrap_or_default().to_string(),
+            title: data["title"].as_str().unwrap_or_default().to_string(),
+            description: data["description"].as_str().unwrap_or_default().to_string(),
+            date_created: data["date_created"].as_str().unwrap_or_default().to_string(),
+            media_type: data["media_type"].as_str().unwrap_or_default().to_string(),
+            center: data["center"].as_str().unwrap_or_default().to_string(),
+            keywords: data["keywords"]
+                .members()
+                .filter_map(|keyword| keyword.as_str())
+                .map(String::from)
+                .collect(),
+            photographer: data["photographer"].as_str().unwrap_or_default().to_string(),
+            thumbnail: object["links"]
+                .members()
+                .find(|link| link["rel"] == "preview")
+                .and_then(|link| link["href"].as_str())
+                .map(String::from),
+            href: object["href"].as_str().unwrap_or_default().to_string(),
+        }
+    }
+}
+
+/// One file of a NIVL asset, e.g. the "orig" or "thumb" rendition of an image
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct NIVLRendition {
+    pub name: String,
+    pub href: String,
+}
+
+impl NIVLRendition {
+    fn from_href(href: &str) -> Self {
+        let file_name = href.rsplit('/').next().unwrap_or(href);
+        // Renditions are named like as11-40-5874~orig.jpg
+        let name = match file_name.rsplit_once('~') {
+            Some((_, rendition)) => rendition.split('.').next().unwrap_or(rendition),
+            None => file_name,
+        };
+        Self {
+            name: name.to_string(),
+            href: href.to_string(),
+        }
+    }
+
+    pub fn extension(&self) -> &str {
+        self.href
+            .rsplit('/')
+            .next()
+            .and_then(|file_name| file_name.rsplit_once('.'))
+            .map_or("", |(_, extension)| extension)
+    }
+
+    pub fn is_caption(&self) -> bool {
+        matches!(self.extension(), "srt" | "vtt")
+    }
+
+    pub fn is_media(&self) -> bool {
+        matches!(
+            self.extension(),
+            "mp4" | "mov" | "m4v" | "webm" | "mp3" | "m4a" | "wav" | "ogg"
+        )
+    }
+}
+
+/// A single subtitle from a .srt or .vtt caption file
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct CaptionCue {
+    pub start: String,
+    pub end: String,
+    pub text: String,
+}
+
+impl CaptionCue {
+    #[tracing::instrument(skip_all, fields(href = %rendition.href))]
+    pub fn get_captions_blocking(rendition: &NIVLRendition) -> Result<Vec<Self>, SpacepixError> {
+        Ok(Self::parse(&get_text_blocking(&rendition.href)?))
+    }
+
+    // SRT and WebVTT both separate cues with blank lines and time them with "start --> end"
+    pub fn parse(captions: &str) -> Vec<Self> {
+        captions
+            .replace("\r\n", "\n")
+            .split("\n\n")
+            .filter_map(|block| {
+                let mut lines = block.lines().skip_while(|line| !line.contains("-->"));
+                let (start, end) = lines.next()?.split_once("-->")?;
+                Some(Self {
+                    start: start.trim().replace(',', "."),
+                    // WebVTT can put cue settings after the end time
+                    end: end.split_whitespace().next()?.replace(',', "."),
+                    text: lines.collect::<Vec<&str>>().join("\n"),
+                })
+            })
+            .collect()
+    }
+}
+
+/// Full details of a single NIVL search result from the /asset and /metadata endpoints
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct NIVLAsset {
+    pub item: NIVLItem,
+    pub renditions: Vec<NIVLRendition>,
+    pub duration: Option<String>, // Only set for video and audio
+    pub captions: Option<Vec<CaptionCue>>, // Loaded on demand from a caption rendition
+}
+
+impl NIVLAsset {
+    #[tracing::instrument(skip_all, fields(nasa_id = %item.nasa_id))]
+    pub fn get_asset_blocking(parser: &Parser, item: &NIVLItem) -> Result<Self, SpacepixError> {
+        let asset_json = get_json_blocking(parser, &parser.nivl_asset_url(&item.nasa_id))?;
+        let mut asset = Self::from_json(&asset_json, item);
+
+        // /metadata only points at the metadata file, which has the untruncated description
+        let location_json = get_json_blocking(parser, &parser.nivl_metadata_url(&item.nasa_id))?;
+        if let Some(location) = location_json["location"].as_str() {
+            asset.apply_metadata(&get_json_blocking(parser, location)?);
+        }
+        Ok(asset)
+    }
+
+    fn from_json(json_obj: &json::JsonValue, item: &NIVLItem) -> Self {
+        Self {
+            item: item.clone(),
+            renditions: json_obj["collection"]["items"]
+                .members()
+                .filter_map(|file| file["href"].as_str())
+                .filter(|href| !href.ends_with("metadata.json"))
+                .map(NIVLRendition::from_href)
+                .collect(),
+            duration: None,
+            captions: None,
+        }
+    }
+
+    fn apply_metadata(&mut self, metadata: &json::JsonValue) {
+        let fields = [
+            ("AVAIL:Title", &mut self.item.title),
+            ("AVAIL:Description", &mut self.item.description),
+            ("AVAIL:Photographer", &mut self.item.photographer),
+            ("AVAIL:DateCreated", &mut self.item.date_created),
+        ];
+        for (key, field) in fields {
+            if let Some(value) = metadata[key].as_str().filter(|value| !value.is_empty()) {
+                *field = value.to_string();
+            }
+        }
+        let keywords: Vec<String> = metadata["AVAIL:Keywords"]
+            .members()
+            .filter_map(|keyword| keyword.as_str())
+            .map(String::from)
+            .collect();
+        if !keywords.is_empty() {
+            self.item.keywords = keywords;
+        }
+        // Which tag holds the duration depends on the container the file came in
+        self.duration = ["QuickTime:Duration", "Composite:Duration", "MPEG:Duration", "XMP:Duration"]
+            .into_iter()
+            .find_map(|key| metadata[key].as_str().map(String::from))
+            .or(self.duration.take());
+    }
+
+    // The file to hand to the system player, the original if there is one
+    pub fn playable(&self) -> Option<&NIVLRendition> {
+        self.renditions
+            .iter()
+            .filter(|rendition| rendition.is_media())
+            .min_by_key(|rendition| rendition.name != "orig")
+    }
+
+    pub fn caption_files(&self) -> impl Iterator<Item = &NIVLRendition> {
+        self.renditions.iter().filter(|rendition| rendition.is_caption())
+    }
+
+    pub fn rendition(&self, name: &str) -> Option<&NIVLRendition> {
+        self.renditions.iter().find(|rendition| rendition.name == name)
+    }
+}
+
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
 pub struct NIVL {
+    pub items: Vec<NIVLItem>,
+    pub total_hits: u32,
+    pub search: NIVLSearch,
+    pub selected: Option<NIVLAsset>,
+}
+
+impl NIVL {
+    // The API refuses to page past the 10,000th result
+    const MAX_HITS: u32 = 10_000;
+
+    #[tracing::instrument(skip_all, fields(search = ?search))]
+    pub fn search_blocking(parser: &Parser, search: &NIVLSearch) -> Result<Self, SpacepixError> {
+        let url = match search.album.as_str() {
+            "" => parser.nivl_search_url(search),
+            album => parser.nivl_album_url(album, search.page),
+        };
+        let json_obj = get_json_blocking(parser, &url)?;
+        Ok(Self::from_json(&json_obj, search))
+    }
+
+    fn from_json(json_obj: &json::JsonValue, search: &NIVLSearch) -> Self {
+        Self {
+            items: json_obj["collection"]["items"]
+                .members()
+                .map(NIVLItem::from_json)
+                .collect(),
+            total_hits: json_obj["collection"]["metadata"]["total_hits"]
+                .as_u32()
+                .unwrap_or_default(),
+            search: search.clone(),
+            selected: None,
+        }
+    }
+
+    pub fn page_count(&self) -> u32 {
+        self.total_hits
+            .min(Self::MAX_HITS)
+            .div_ceil(self.search.page_size.max(1))
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub enum MarsRover {
+    #[default]
+    Curiosity,
+    Perseverance,
+    Opportunity,
+    Spirit,
+}
+
+impl MarsRover {
+    pub const ALL: [MarsRover; 4] = [
+        MarsRover::Curiosity,
+        MarsRover::Perseverance,
+        MarsRover::Opportunity,
+        MarsRover::Spirit,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            MarsRover::Curiosity => "curiosity",
+            MarsRover::Perseverance => "perseverance",
+            MarsRover::Opportunity => "opportunity",
+            MarsRover::Spirit => "spirit",
+        }
+    }
+
+    // Every camera the API knows for this rover, used until the manifest says which ones shot on a sol
+    pub fn cameras(&self) -> &'static [&'static str] {
+        match self {
+            MarsRover::Curiosity => &["FHAZ", "RHAZ", "MAST", "CHEMCAM", "MAHLI", "MARDI", "NAVCAM"],
+            MarsRover::Perseverance => &[
+                "EDL_RUCAM",
+                "EDL_RDCAM",
+                "EDL_DDCAM",
+                "EDL_PUCAM1",
+                "EDL_PUCAM2",
+                "NAVCAM_LEFT",
+                "NAVCAM_RIGHT",
+                "MCZ_LEFT",
+                "MCZ_RIGHT",
+                "FRONT_HAZCAM_LEFT_A",
+                "FRONT_HAZCAM_RIGHT_A",
+                "REAR_HAZCAM_LEFT",
+                "REAR_HAZCAM_RIGHT",
+                "SKYCAM",
+                "SHERLOC_WATSON",
+                "SUPERCAM_RMI",
+                "LCAM",
+            ],
+            MarsRover::Opportunity | MarsRover::Spirit => {
+                &["FHAZ", "RHAZ", "NAVCAM", "PANCAM", "MINITES"]
+            }
+        }
+    }
+}
+
+impl Display for MarsRover {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
 
+/// Photos are looked up either by Martian sol or by Earth date (YYYY-MM-DD)
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub enum MarsPhotoDate {
+    Sol(u32),
+    EarthDate(String),
 }
 
-impl Default for NIVL {
+impl Default for MarsPhotoDate {
     fn default() -> Self {
-        Self {}
+        MarsPhotoDate::Sol(1000)
     }
 }
 
-#[cfg(test)]
-mod tests {
-    #[allow(unused_imports)]
-    use super::Apod;
+/// Everything needed to request one page of rover photos
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct MarsPhotoQuery {
+    pub rover: MarsRover,
+    pub date: MarsPhotoDate,
+    pub camera: Option<String>,
+    pub page: u32,
+}
 
-    #[test]
-    fn test_get_apod_data_blocking() {
-        todo!()
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct MarsPhoto {
+    pub id: u64,
+    pub sol: u32,
+    pub camera: String,
+    pub camera_full_name: String,
+    pub img_src: String,
+    pub earth_date: String,
+}
+
+impl MarsPhoto {
+    fn from_json(object: &json::JsonValue) -> Self {
+        Self {
+            id: object["id"].as_u64().unwrap_or_default(),
+            sol: object["sol"].as_u32().unwrap_or_default(),
+            camera: object["camera"]["name"].as_str().unwrap_or_default().to_string(),
+            camera_full_name: object["camera"]["full_name"]
+                .as_str()
+                .unwrap_or_default()
+                .to_string(),
+            img_src: object["img_src"].as_str().unwrap_or_default().to_string(),
+            earth_date: object["earth_date"].as_str().unwrap_or_default().to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct MarsRoverPhotos {
+    pub query: MarsPhotoQuery,
+    pub photos: Vec<MarsPhoto>,
+}
+
+impl MarsRoverPhotos {
+    // The API serves photos 25 at a time
+    pub const PAGE_SIZE: usize = 25;
+
+    #[tracing::instrument(skip_all, fields(query = ?query))]
+    pub fn get_photos_blocking(parser: &Parser, query: &MarsPhotoQuery) -> Result<Self, SpacepixError> {
+        let json_obj = get_json_blocking(parser, &parser.mars_photos_url(query))?;
+        Ok(Self {
+            query: query.clone(),
+            photos: json_obj["photos"].members().map(MarsPhoto::from_json).collect(),
+        })
+    }
+
+    pub fn has_next_page(&self) -> bool {
+        self.photos.len() == Self::PAGE_SIZE
+    }
+}
+
+/// How many photos a rover took on a single sol and with which cameras
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct MarsSol {
+    pub sol: u32,
+    pub earth_date: String,
+    pub total_photos: u32,
+    pub cameras: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct MarsRoverManifest {
+    pub rover: MarsRover,
+    pub landing_date: String,
+    pub launch_date: String,
+    pub status: String,
+    pub max_sol: u32,
+    pub max_date: String,
+    pub total_photos: u32,
+    pub sols: Vec<MarsSol>,
+}
+
+impl MarsRoverManifest {
+    #[tracing::instrument(skip_all, fields(rover = ?rover))]
+    pub fn get_manifest_blocking(parser: &Parser, rover: MarsRover) -> Result<Self, SpacepixError> {
+        let json_obj = get_json_blocking(parser, &parser.mars_manifest_url(rover))?;
+        Ok(Self::from_json(&json_obj, rover))
+    }
+
+    fn from_json(json_obj: &json::JsonValue, rover: MarsRover) -> Self {
+        let manifest = &json_obj["photo_manifest"];
+        Self {
+            rover,
+            landing_date: manifest["landing_date"].as_str().unwrap_or_default().to_string(),
+            launch_date: manifest["launch_date"].as_str().unwrap_or_default().to_string(),
+            status: manifest["status"].as_str().unwrap_or_default().to_string(),
+            max_sol: manifest["max_sol"].as_u32().unwrap_or_default(),
+            max_date: manifest["max_date"].as_str().unwrap_or_default().to_string(),
+            total_photos: manifest["total_photos"].as_u32().unwrap_or_default(),
+            sols: manifest["photos"]
+                .members()
+                .map(|sol| MarsSol {
+                    sol: sol["sol"].as_u32().unwrap_or_default(),
+                    earth_date: sol["earth_date"].as_str().unwrap_or_default().to_string(),
+                    total_photos: sol["total_photos"].as_u32().unwrap_or_default(),
+                    cameras: sol["cameras"]
+                        .members()
+                        .filter_map(|camera| camera.as_str())
+                        .map(String::from)
+                        .collect(),
+                })
+                .collect(),
+        }
+    }
+
+    // Sols without photos are left out of the manifest
+    pub fn sol(&self, sol: u32) -> Option<&MarsSol> {
+        self.sols.iter().find(|entry| entry.sol == sol)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub enum EpicCollection {
+    #[default]
+    Natural,
+    Enhanced,
+}
+
+impl EpicCollection {
+    pub fn name(&self) -> &'static str {
+        match self {
+            EpicCollection::Natural => "natural",
+            EpicCollection::Enhanced => "enhanced",
+        }
+    }
+}
+
+/// Which size of an EPIC image to pull from the archive
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+pub enum EpicImageFormat {
+    Png, // 2048x2048
+    Jpg, // 1024x1024
+    Thumbs,
+}
+
+impl EpicImageFormat {
+    // (archive folder, file extension)
+    pub fn path(&self) -> (&'static str, &'static str) {
+        match self {
+            EpicImageFormat::Png => ("png", "png"),
+            EpicImageFormat::Jpg => ("jpg", "jpg"),
+            EpicImageFormat::Thumbs => ("thumbs", "jpg"),
+        }
+    }
+}
+
+/**
+ * A single full disk image from the DSCOVR EPIC camera
+ * centroid tuple key (lat, lon)
+ * *_position tuple key (x, y, z) J2000 position in kilometers relative to Earth
+ */
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct EpicImage {
+    pub identifier: String,
+    pub caption: String,
+    pub image: String,
+    pub date: String,
+    pub centroid: (f64, f64), // (lat, lon)
+    pub dscovr_position: (f64, f64, f64), // (x, y, z)
+    pub lunar_position: (f64, f64, f64), // (x, y, z)
+    pub sun_position: (f64, f64, f64), // (x, y, z)
+}
+
+impl EpicImage {
+    fn from_json(object: &json::JsonValue) -> Self {
+        let position = |key: &str| {
+            (
+                object[key]["x"].as_f64().unwrap_or_default(),
+                object[key]["y"].as_f64().unwrap_or_default(),
+                object[key]["z"].as_f64().unwrap_or_default(),
+            )
+        };
+        Self {
+            identifier: object["identifier"].as_str().unwrap_or_default().to_string(),
+            caption: object["caption"].as_str().unwrap_or_default().to_string(),
+            image: object["image"].as_str().unwrap_or_default().to_string(),
+            date: object["date"].as_str().unwrap_or_default().to_string(),
+            centroid: (
+                object["centroid_coordinates"]["lat"].as_f64().unwrap_or_default(),
+                object["centroid_coordinates"]["lon"].as_f64().unwrap_or_default(),
+            ),
+            dscovr_position: position("dscovr_j2000_position"),
+            lunar_position: position("lunar_j2000_position"),
+            sun_position: position("sun_j2000_position"),
+        }
+    }
+
+    // The archive is laid out by day, "2019-05-30 00:50:27" lives under 2019/05/30
+    pub fn date_path(&self) -> String {
+        self.date
+            .split_whitespace()
+            .next()
+            .unwrap_or_default()
+            .replace('-', "/")
+    }
+
+    // Only this request carries the key, the window hands egui the bytes under epic_image_uri()
+    #[tracing::instrument(skip_all, fields(collection = ?collection, image = %self.image))]
+    pub fn get_image_blocking(
+        &self,
+        parser: &Parser,
+        collection: EpicCollection,
+        format: EpicImageFormat,
+    ) -> Result<Vec<u8>, SpacepixError> {
+        get_bytes_blocking(parser, &parser.epic_image_url(collection, self, format))
+    }
+
+    // Distance from the center of the Earth in kilometers
+    pub fn distance(position: (f64, f64, f64)) -> f64 {
+        (position.0.powi(2) + position.1.powi(2) + position.2.powi(2)).sqrt()
+    }
+}
+
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct EpicDay {
+    pub collection: EpicCollection,
+    pub images: Vec<EpicImage>,
+}
+
+impl EpicDay {
+    // An empty date gets the most recent day with images
+    #[tracing::instrument(skip_all, fields(collection = ?collection, date))]
+    pub fn get_day_blocking(
+        parser: &Parser,
+        collection: EpicCollection,
+        date: &str,
+    ) -> Result<Self, SpacepixError> {
+        let json_obj = get_json_blocking(parser, &parser.epic_url(collection, date))?;
+        Ok(Self {
+            collection,
+            images: json_obj.members().map(EpicImage::from_json).collect(),
+        })
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct EonetCategory {
+    pub id: String,
+    pub title: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct EonetSource {
+    pub id: String,
+    pub title: String,
+    pub url: String,
+}
+
+/**
+ * Where an event was at a point in time
+ * coordinates are (lon, lat), one pair for a Point and the outer ring for a Polygon
+ * magnitude tuple key (value, unit), e.g. (45.0, "kts") for a storm's wind speed
+ */
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct EonetGeometry {
+    pub date: String,
+    pub kind: String,
+    pub coordinates: Vec<(f64, f64)>,
+    pub magnitude: Option<(f64, String)>,
+}
+
+impl EonetGeometry {
+    fn from_json(object: &json::JsonValue) -> Self {
+        let coordinates = &object["coordinates"];
+        let points = match object["type"].as_str() {
+            Some("Polygon") => coordinates[0].members().collect(),
+            _ => vec![coordinates],
+        };
+        Self {
+            date: object["date"].as_str().unwrap_or_default().to_string(),
+            kind: object["type"].as_str().unwrap_or_default().to_string(),
+            coordinates: points
+                .into_iter()
+                .filter_map(|point| Some((point[0].as_f64()?, point[1].as_f64()?)))
+                .collect(),
+            magnitude: object["magnitudeValue"].as_f64().map(|value| {
+                (
+                    value,
+                    object["magnitudeUnit"].as_str().unwrap_or_default().to_string(),
+                )
+            }),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct EonetEvent {
+    pub id: String,
+    pub title: String,
+    pub description: String,
+    pub link: String,
+    pub closed: Option<String>,
+    pub categories: Vec<EonetCategory>,
+    pub sources: Vec<EonetSource>,
+    pub geometry: Vec<EonetGeometry>,
+}
+
+impl EonetEvent {
+    fn from_json(object: &json::JsonValue) -> Self {
+        Self {
+            id: object["id"].as_str().unwrap_or_default().to_string(),
+            title: object["title"].as_str().unwrap_or_default().to_string(),
+            description: object["description"].as_str().unwrap_or_default().to_string(),
+            link: object["link"].as_str().unwrap_or_default().to_string(),
+            closed: object["closed"].as_str().map(String::from),
+            categories: object["categories"]
+                .members()
+                .map(|category| EonetCategory {
+                    id: category["id"].as_str().unwrap_or_default().to_string(),
+                    title: category["title"].as_str().unwrap_or_default().to_string(),
+                })
+                .collect(),
+            sources: object["sources"]
+                .members()
+                .map(|source| EonetSource {
+                    id: source["id"].as_str().unwrap_or_default().to_string(),
+                    title: String::default(),
+                    url: source["url"].as_str().unwrap_or_default().to_string(),
+                })
+                .collect(),
+            geometry: object["geometry"].members().map(EonetGeometry::from_json).collect(),
+        }
+    }
+}
+
+/// Filters for the EONET events endpoint
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct EonetQuery {
+    pub status: String, // "open", "closed" or "all"
+    pub category: Option<String>,
+    pub source: Option<String>,
+    pub days: u32,
+}
+
+impl Default for EonetQuery {
+    fn default() -> Self {
+        Self {
+            status: String::from("open"),
+            category: None,
+            source: None,
+            days: 30,
+        }
+    }
+}
+
+impl EonetQuery {
+    pub fn params(&self) -> Vec<(&str, String)> {
+        let mut params = vec![("status", self.status.clone()), ("days", self.days.to_string())];
+        if let Some(category) = &self.category {
+            params.push(("category", category.clone()));
+        }
+        if let Some(source) = &self.source {
+            params.push(("source", source.clone()));
+        }
+        params
+    }
+}
+
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct Eonet {
+    pub events: Vec<EonetEvent>,
+    pub categories: Vec<EonetCategory>,
+    pub sources: Vec<EonetSource>,
+}
+
+impl Eonet {
+    #[tracing::instrument(skip_all, fields(query = ?query))]
+    pub fn get_events_blocking(&mut self, parser: &Parser, query: &EonetQuery) -> Result<&mut Self, SpacepixError> {
+        let json_obj = get_json_blocking(parser, &parser.eonet_events_url(query))?;
+        self.events = json_obj["events"].members().map(EonetEvent::from_json).collect();
+        Ok(self)
+    }
+
+    // Categories and sources only change when NASA adds a new feed, so these are fetched once
+    #[tracing::instrument(skip_all, fields(profile = parser.profile()))]
+    pub fn get_filters_blocking(&mut self, parser: &Parser) -> Result<&mut Self, SpacepixError> {
+        let categories = get_json_blocking(parser, &parser.urls.eonet_categories)?;
+        let sources = get_json_blocking(parser, &parser.urls.eonet_sources)?;
+        self.categories = categories["categories"]
+            .members()
+            .map(|category| EonetCategory {
+                id: category["id"].as_str().unwrap_or_default().to_string(),
+                title: category["title"].as_str().unwrap_or_default().to_string(),
+            })
+            .collect();
+        self.sources = sources["sources"]
+            .members()
+            .map(|source| EonetSource {
+                id: source["id"].as_str().unwrap_or_default().to_string(),
+                title: source["title"].as_str().unwrap_or_default().to_string(),
+                url: source["source"].as_str().unwrap_or_default().to_string(),
+            })
+            .collect();
+        Ok(self)
+    }
+}
+
+/**
+ * An object on the CNEOS Sentry risk list
+ * impact_probability is cumulative over every potential impact
+ * palermo_scale tuple key (cumulative, max)
+ */
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct SentryObject {
+    pub des: String,
+    pub fullname: String,
+    pub impact_probability: f64,
+    pub palermo_scale: (f64, f64), // (cumulative, max)
+    pub torino_scale: Option<u8>,
+    pub range: String, // Years with potential impacts, e.g. "2056-2113"
+    pub n_imp: u32,
+    pub diameter: Option<f64>, // Kilometers
+    pub last_obs: String,
+}
+
+impl SentryObject {
+    fn from_json(object: &json::JsonValue) -> Self {
+        Self {
+            des: object["des"].as_str().unwrap_or_default().to_string(),
+            fullname: object["fullname"].as_str().unwrap_or_default().trim().to_string(),
+            impact_probability: parse_f64(&object["ip"]).unwrap_or_default(),
+            palermo_scale: (
+                parse_f64(&object["ps_cum"]).unwrap_or_default(),
+                parse_f64(&object["ps_max"]).unwrap_or_default(),
+            ),
+            torino_scale: parse_f64(&object["ts_max"]).map(|ts| ts as u8),
+            range: object["range"].as_str().unwrap_or_default().to_string(),
+            n_imp: parse_f64(&object["n_imp"]).unwrap_or_default() as u32,
+            diameter: parse_f64(&object["diameter"]),
+            last_obs: object["last_obs"].as_str().unwrap_or_default().to_string(),
+        }
+    }
+}
+
+/// One potential impact of a Sentry object
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct SentryImpact {
+    pub date: String,
+    pub impact_probability: f64,
+    pub palermo_scale: f64,
+    pub torino_scale: Option<u8>,
+    pub energy: Option<f64>, // Megatons of TNT
+}
+
+/// Sentry finds objects either by their designation or by their JPL SPK-ID
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub enum SentryLookup {
+    Des(String),
+    Spk(String),
+}
+
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct SentryRecord {
+    pub object: SentryObject,
+    pub impacts: Vec<SentryImpact>,
+}
+
+impl SentryRecord {
+    #[tracing::instrument(skip_all, fields(lookup = ?lookup))]
+    pub fn get_record_blocking(parser: &Parser, lookup: &SentryLookup) -> Result<Self, SpacepixError> {
+        let json_obj = get_json_blocking(parser, &parser.sentry_object_url(lookup))?;
+        Self::from_json(&json_obj, lookup)
+    }
+
+    // Objects dropped from the risk list, or never on it, still come back with HTTP 200
+    fn from_json(json_obj: &json::JsonValue, lookup: &SentryLookup) -> Result<Self, SpacepixError> {
+        if let Some(error) = json_obj["error"].as_str() {
+            let (SentryLookup::Des(name) | SentryLookup::Spk(name)) = lookup;
+            return Err(SpacepixError::NotFound(match json_obj["removed"].as_str() {
+                Some(removed) => format!("{} was removed from Sentry on {}", name.trim(), removed),
+                None => format!("{} isn't on Sentry's risk list ({})", name.trim(), error),
+            }));
+        }
+        Ok(Self {
+            object: SentryObject::from_json(&json_obj["summary"]),
+            impacts: json_obj["data"]
+                .members()
+                .map(|impact| SentryImpact {
+                    date: impact["date"].as_str().unwrap_or_default().to_string(),
+                    impact_probability: parse_f64(&impact["ip"]).unwrap_or_default(),
+                    palermo_scale: parse_f64(&impact["ps"]).unwrap_or_default(),
+                    torino_scale: parse_f64(&impact["ts"]).map(|ts| ts as u8),
+                    energy: parse_f64(&impact["energy"]),
+                })
+                .collect(),
+        })
+    }
+}
+
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct Sentry {
+    pub objects: Vec<SentryObject>,
+    pub selected: Option<SentryRecord>,
+}
+
+impl Sentry {
+    #[tracing::instrument(skip_all, fields(profile = parser.profile()))]
+    pub fn get_objects_blocking(&mut self, parser: &Parser) -> Result<&mut Self, SpacepixError> {
+        let json_obj = get_json_blocking(parser, &parser.urls.sentry)?;
+        self.objects = json_obj["data"].members().map(SentryObject::from_json).collect();
+        Ok(self)
+    }
+}
+
+/**
+ * A bolide reported to CNEOS by US government sensors
+ * energy is the total radiated energy in joules x 10^10
+ * impact_energy is the estimated impact energy in kilotons of TNT
+ * lat/lon are signed degrees, north and east positive
+ */
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct Fireball {
+    pub date: String,
+    pub energy: f64,
+    pub impact_energy: f64,
+    pub lat: Option<f64>,
+    pub lon: Option<f64>,
+    pub altitude: Option<f64>, // Kilometers
+    pub velocity: Option<f64>, // Kilometers per second
+}
+
+impl Fireball {
+    // Rows are plain arrays, so values are looked up through the "fields" header
+    fn from_row(fields: &[&str], row: &json::JsonValue) -> Self {
+        let field = |name: &str| match fields.iter().position(|field| *field == name) {
+            Some(i) => &row[i],
+            None => &json::JsonValue::Null,
+        };
+        let signed = |value: &str, direction: &str, negative: &str| {
+            parse_f64(field(value)).map(|degrees| match field(direction).as_str() {
+                Some(dir) if dir == negative => -degrees,
+                _ => degrees,
+            })
+        };
+        Self {
+            date: field("date").as_str().unwrap_or_default().to_string(),
+            energy: parse_f64(field("energy")).unwrap_or_default(),
+            impact_energy: parse_f64(field("impact-e")).unwrap_or_default(),
+            lat: signed("lat", "lat-dir", "S"),
+            lon: signed("lon", "lon-dir", "W"),
+            altitude: parse_f64(field("alt")),
+            velocity: parse_f64(field("vel")),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct Fireballs {
+    pub events: Vec<Fireball>,
+}
+
+impl Fireballs {
+    // The most recent events first, optionally only those on or after date_min (YYYY-MM-DD)
+    #[tracing::instrument(skip_all, fields(limit, date_min))]
+    pub fn get_fireballs_blocking(
+        parser: &Parser,
+        limit: u32,
+        date_min: &str,
+    ) -> Result<Self, SpacepixError> {
+        let json_obj = get_json_blocking(parser, &parser.fireball_url(limit, date_min))?;
+        Ok(Self::from_json(&json_obj))
+    }
+
+    fn from_json(json_obj: &json::JsonValue) -> Self {
+        let fields: Vec<&str> = json_obj["fields"]
+            .members()
+            .filter_map(|field| field.as_str())
+            .collect();
+        Self {
+            events: json_obj["data"]
+                .members()
+                .map(|row| Fireball::from_row(&fields, row))
+                .collect(),
+        }
+    }
+}
+
+/// Parameters for JPL's close-approach data API, an alternative to the 7 day NeoWs feed
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct CadQuery {
+    pub date_min: String,
+    pub date_max: String,
+    pub dist_max: String, // au, or with a unit like "1LD"
+    pub include_comets: bool,
+}
+
+impl Default for CadQuery {
+    fn default() -> Self {
+        Self {
+            date_min: String::from("now"),
+            date_max: String::from("+60"),
+            dist_max: String::from("0.05"),
+            include_comets: false,
+        }
+    }
+}
+
+impl CadQuery {
+    pub fn params(&self) -> Vec<(&str, String)> {
+        let mut params: Vec<(&str, String)> = [
+            ("date-min", &self.date_min),
+            ("date-max", &self.date_max),
+            ("dist-max", &self.dist_max),
+        ]
+        .into_iter()
+        .filter(|(_, value)| !value.trim().is_empty())
+        .map(|(name, value)| (name, value.trim().to_string()))
+        .collect();
+        params.push(("comet", self.include_comets.to_string()));
+        params.push(("fullname", String::from("true")));
+        params
+    }
+}
+
+impl NearEarthObject {
+    const KM_PER_AU: f64 = 149_597_870.7;
+    const LD_PER_AU: f64 = 389.174;
+    const FEET_PER_METER: f64 = 3.28084;
+    const MILES_PER_KM: f64 = 0.621371;
+
+    // Turn a CAD row into the same shape NeoWs gives us
+    fn from_cad_row(fields: &[&str], row: &json::JsonValue) -> Self {
+        let field = |name: &str| match fields.iter().position(|field| *field == name) {
+            Some(i) => &row[i],
+            None => &json::JsonValue::Null,
+        };
+        let des = field("des").as_str().unwrap_or_default().to_string();
+        let full_date = field("cd").as_str().unwrap_or_default().to_string(); // e.g. 2024-Jan-01 12:34
+        let date = chrono::NaiveDateTime::parse_from_str(&full_date, "%Y-%b-%d %H:%M").ok();
+        let au = parse_f64(field("dist")).unwrap_or_default();
+        let km_per_second = parse_f64(field("v_rel")).unwrap_or_default();
+        let km = au * Self::KM_PER_AU;
+
+        // Like NeoWs, bracket the diameter with albedos of 0.25 and 0.05
+        let (meters_min, meters_max) = match parse_f64(field("h")) {
+            Some(h) => {
+                let diameter = |albedo: f64| 1_329_000.0 / albedo.sqrt() * 10f64.powf(-h / 5.0);
+                (diameter(0.25), diameter(0.05))
+            }
+            None => (0.0, 0.0),
+        };
+
+        Self::new(
+            des.clone(),
+            des.clone(),
+            field("fullname")
+                .as_str()
+                .map_or(des, |name| name.trim().to_string()),
+            (
+                (
+                    (meters_min * Self::FEET_PER_METER) as f32,
+                    (meters_max * Self::FEET_PER_METER) as f32,
+                ),
+                (meters_min as f32, meters_max as f32),
+            ),
+            None, // CAD doesn't classify potentially hazardous asteroids
+            date.map_or(String::default(), |date| date.format("%Y-%m-%d").to_string()),
+            full_date,
+            date.map_or(0, |date| date.and_utc().timestamp_millis().max(0) as u64),
+            (
+                km_per_second.to_string(),
+                (km_per_second * 3600.0).to_string(),
+                (km_per_second * 3600.0 * Self::MILES_PER_KM).to_string(),
+            ),
+            (
+                au.to_string(),
+                (au * Self::LD_PER_AU).to_string(),
+                km.to_string(),
+                (km * Self::MILES_PER_KM).to_string(),
+            ),
+            String::from("Earth"),
+            false,
+        )
+    }
+}
+
+impl NEOFeed {
+    // Retrieve close approaches from JPL's CAD API over any date range
+    #[tracing::instrument(skip_all, fields(query = ?query))]
+    pub fn get_cad_feed_blocking(
+        &mut self,
+        parser: &Parser,
+        query: &CadQuery,
+    ) -> Result<&mut NEOFeed, SpacepixError> {
+        let json_obj = get_json_blocking(parser, &parser.cad_url(query))?;
+        self.set_cad_objects(&json_obj);
+        Ok(self)
+    }
+
+    fn set_cad_objects(&mut self, json_obj: &json::JsonValue) {
+        let fields: Vec<&str> = json_obj["fields"]
+            .members()
+            .filter_map(|field| field.as_str())
+            .collect();
+        self.links = Links::default();
+        self.near_earth_objects = json_obj["data"]
+            .members()
+            .map(|row| NearEarthObject::from_cad_row(&fields, row))
+            .collect();
+        self.element_count = self.near_earth_objects.len() as u32;
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub enum ExoplanetTable {
+    Ps, // Every published solution, one row per planet per paper
+    #[default]
+    PsCompPars, // One row per planet, parameters combined from several papers
+}
+
+impl ExoplanetTable {
+    pub const ALL: [ExoplanetTable; 2] = [ExoplanetTable::PsCompPars, ExoplanetTable::Ps];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ExoplanetTable::Ps => "ps",
+            ExoplanetTable::PsCompPars => "pscomppars",
+        }
+    }
+}
+
+impl Display for ExoplanetTable {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ExoplanetTable::Ps => write!(f, "Planetary Systems (ps)"),
+            ExoplanetTable::PsCompPars => write!(f, "Composite Parameters (pscomppars)"),
+        }
+    }
+}
+
+/**
+ * Filters for an Exoplanet Archive TAP query, turned into ADQL by adql()
+ * Bounds are kept as text so they can be edited in place, blank or unparsable bounds are ignored
+ * radius is in Earth radii, mass in Earth masses and period in days
+ */
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct ExoplanetQuery {
+    pub table: ExoplanetTable,
+    pub discovery_method: String,
+    pub year_min: String,
+    pub year_max: String,
+    pub radius_min: String,
+    pub radius_max: String,
+    pub mass_min: String,
+    pub mass_max: String,
+    pub period_min: String,
+    pub period_max: String,
+    pub limit: u32,
+}
+
+impl Default for ExoplanetQuery {
+    fn default() -> Self {
+        Self {
+            table: ExoplanetTable::default(),
+            discovery_method: String::default(),
+            year_min: String::default(),
+            year_max: String::default(),
+            radius_min: String::default(),
+            radius_max: String::default(),
+            mass_min: String::default(),
+            mass_max: String::default(),
+            period_min: String::default(),
+            period_max: String::default(),
+            limit: 500,
+        }
+    }
+}
+
+impl ExoplanetQuery {
+    pub const DISCOVERY_METHODS: [&'static str; 11] = [
+        "Transit",
+        "Radial Velocity",
+        "Microlensing",
+        "Imaging",
+        "Transit Timing Variations",
+        "Eclipse Timing Variations",
+        "Orbital Brightness Modulation",
+        "Pulsar Timing",
+        "Astrometry",
+        "Pulsation Timing Variations",
+        "Disk Kinematics",
+    ];
+    const COLUMNS: &'static str = "pl_name,hostname,discoverymethod,disc_year,pl_rade,pl_bmasse,pl_orbper";
+
+    pub fn adql(&self) -> String {
+        let mut conditions: Vec<String> = Vec::default();
+        if self.table == ExoplanetTable::Ps {
+            // Without this ps returns every solution for a planet, not just the archive's pick
+            conditions.push(String::from("default_flag=1"));
+        }
+        if !self.discovery_method.trim().is_empty() {
+            // Quotes are doubled so the method can't end the string early
+            conditions.push(format!(
+                "discoverymethod='{}'",
+                self.discovery_method.trim().replace('\'', "''")
+            ));
+        }
+        for (column, min, max) in [
+            ("disc_year", &self.year_min, &self.year_max),
+            ("pl_rade", &self.radius_min, &self.radius_max),
+            ("pl_bmasse", &self.mass_min, &self.mass_max),
+            ("pl_orbper", &self.period_min, &self.period_max),
+        ] {
+            if let Ok(min) = min.trim().parse::<f64>() {
+                conditions.push(format!("{}>={}", column, min));
+            }
+            if let Ok(max) = max.trim().parse::<f64>() {
+                conditions.push(format!("{}<={}", column, max));
+            }
+        }
+
+        let mut adql = format!("select top {} {} from {}", self.limit, Self::COLUMNS, self.table.name());
+        if !conditions.is_empty() {
+            adql.push_str(" where ");
+            adql.push_str(&conditions.join(" and "));
+        }
+        adql.push_str(" order by disc_year desc");
+        adql
+    }
+}
+
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct Exoplanet {
+    pub name: String,
+    pub host: String,
+    pub discovery_method: String,
+    pub discovery_year: Option<u32>,
+    pub radius: Option<f64>, // Earth radii
+    pub mass: Option<f64>,   // Earth masses
+    pub period: Option<f64>, // Days
+}
+
+impl Exoplanet {
+    fn from_json(json_obj: &json::JsonValue) -> Self {
+        Self {
+            name: json_obj["pl_name"].as_str().unwrap_or_default().to_string(),
+            host: json_obj["hostname"].as_str().unwrap_or_default().to_string(),
+            discovery_method: json_obj["discoverymethod"].as_str().unwrap_or_default().to_string(),
+            discovery_year: json_obj["disc_year"].as_u32(),
+            radius: json_obj["pl_rade"].as_f64(),
+            mass: json_obj["pl_bmasse"].as_f64(),
+            period: json_obj["pl_orbper"].as_f64(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct Exoplanets {
+    pub planets: Vec<Exoplanet>,
+}
+
+impl Exoplanets {
+    #[tracing::instrument(skip_all, fields(query = ?query))]
+    pub fn get_exoplanets_blocking(parser: &Parser, query: &ExoplanetQuery) -> Result<Self, SpacepixError> {
+        let json_obj = get_json_blocking(parser, &parser.exoplanet_url(query))?;
+        Ok(Self::from_json(&json_obj))
+    }
+
+    // The TAP service answers format=json with a plain array of rows
+    fn from_json(json_obj: &json::JsonValue) -> Self {
+        Self {
+            planets: json_obj.members().map(Exoplanet::from_json).collect(),
+        }
+    }
+}
+
+/**
+ * A spot to look at in Landsat imagery
+ * lat/lon are signed degrees, north and east positive
+ * dim is the width and height of the image in degrees
+ */
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct EarthLocation {
+    pub name: String,
+    pub lat: f64,
+    pub lon: f64,
+    pub dim: f64,
+}
+
+impl Default for EarthLocation {
+    fn default() -> Self {
+        Self {
+            name: String::from("Houston"),
+            lat: 29.78,
+            lon: -95.33,
+            dim: 0.1,
+        }
+    }
+}
+
+impl EarthLocation {
+    pub fn params(&self, date: &str) -> String {
+        format!("lon={}&lat={}&date={}&dim={}", self.lon, self.lat, date.trim(), self.dim)
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct EarthAsset {
+    pub id: String,
+    pub date: String, // e.g. 2014-02-04T03:30:01.210000
+}
+
+impl EarthAsset {
+    fn from_json(json_obj: &json::JsonValue) -> Self {
+        Self {
+            id: json_obj["id"].as_str().unwrap_or_default().to_string(),
+            date: json_obj["date"].as_str().unwrap_or_default().to_string(),
+        }
+    }
+
+    // The YYYY-MM-DD part of the acquisition time, which is what the imagery endpoint wants
+    pub fn day(&self) -> &str {
+        self.date.get(..10).unwrap_or(&self.date)
+    }
+
+    // Only this request carries the key, the window hands egui the bytes under earth_imagery_uri()
+    #[tracing::instrument(skip_all, fields(location = %location.name, day = self.day()))]
+    pub fn get_image_blocking(&self, parser: &Parser, location: &EarthLocation) -> Result<Vec<u8>, SpacepixError> {
+        get_bytes_blocking(parser, &parser.earth_imagery_url(location, self.day()))
+    }
+}
+
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct EarthAssets {
+    pub location: EarthLocation,
+    pub assets: Vec<EarthAsset>, // Oldest first
+}
+
+impl EarthAssets {
+    // Evenly spaced dates from begin to end (YYYY-MM-DD), None if either date doesn't parse
+    pub fn sample_dates(begin: &str, end: &str, samples: u32) -> Option<Vec<String>> {
+        let begin = chrono::NaiveDate::parse_from_str(begin.trim(), "%Y-%m-%d").ok()?;
+        let end = chrono::NaiveDate::parse_from_str(end.trim(), "%Y-%m-%d").ok()?;
+        let (begin, end) = (begin.min(end), begin.max(end));
+        let days = (end - begin).num_days();
+        let samples = samples.max(1) as i64;
+        let mut dates: Vec<String> = (0..samples)
+            .map(|i| match samples {
+                1 => begin,
+                _ => begin + chrono::Duration::days(days * i / (samples - 1)),
+            })
+            .map(|date| date.format("%Y-%m-%d").to_string())
+            .collect();
+        dates.dedup();
+        Some(dates)
+    }
+
+    // The assets endpoint only answers with the acquisition closest to a date, so ask about each
+    // sampled date and keep the distinct acquisitions
+    #[tracing::instrument(skip_all, fields(location = ?location, dates = dates.len()))]
+    pub fn get_assets_blocking(
+        parser: &Parser,
+        location: &EarthLocation,
+        dates: &[String],
+    ) -> Result<Self, SpacepixError> {
+        let mut earth_assets = Self {
+            location: location.clone(),
+            assets: Vec::default(),
+        };
+        let mut error = None;
+        for date in dates {
+            match get_json_blocking(parser, &parser.earth_assets_url(location, date)) {
+                Ok(json_obj) => earth_assets.add_asset(&json_obj),
+                // A bad key or a used up hourly limit fails every remaining date the same way,
+                // and each try would still count against the key
+                Err(e @ SpacepixError::Status { status: 429, .. }) => return Err(e),
+                Err(e) if !e.is_retryable() => return Err(e),
+                // Don't sit through a timeout for every remaining date
+                Err(e) if e.is_unreachable() => {
+                    error = Some(e);
+                    break;
+                }
+                Err(e) => error = Some(e),
+            }
+        }
+        match (earth_assets.assets.is_empty(), error) {
+            (true, Some(e)) => Err(e),
+            _ => Ok(earth_assets),
+        }
+    }
+
+    fn add_asset(&mut self, json_obj: &json::JsonValue) {
+        let asset = EarthAsset::from_json(json_obj);
+        if asset.date.is_empty() || self.assets.iter().any(|known| known.id == asset.id) {
+            return;
+        }
+        self.assets.push(asset);
+        self.assets.sort_by(|a, b| a.date.cmp(&b.date));
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub enum TechTransferCategory {
+    #[default]
+    Patent,
+    Software,
+    Spinoff,
+}
+
+impl TechTransferCategory {
+    pub const ALL: [TechTransferCategory; 3] = [
+        TechTransferCategory::Patent,
+        TechTransferCategory::Software,
+        TechTransferCategory::Spinoff,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            TechTransferCategory::Patent => "patent",
+            TechTransferCategory::Software => "software",
+            TechTransferCategory::Spinoff => "spinoff",
+        }
+    }
+}
+
+impl Display for TechTransferCategory {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TechTransferCategory::Patent => write!(f, "Patents"),
+            TechTransferCategory::Software => write!(f, "Software"),
+            TechTransferCategory::Spinoff => write!(f, "Spinoffs"),
+        }
+    }
+}
+
+/**
+ * One TechTransfer search result
+ * The API sends each result as a plain array:
+ * [id, case number, title, description, reference, category, ..., image url, ...]
+ */
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct TechTransferResult {
+    pub case_number: String,
+    pub title: String,
+    pub description: String,
+    pub category: String,
+    pub image: Option<String>,
+    pub url: String, // The result's page on the NASA website
+}
+
+impl TechTransferResult {
+    fn from_row(category: TechTransferCategory, row: &json::JsonValue) -> Self {
+        let text = |i: usize| strip_html(row[i].as_str().unwrap_or_default());
+        let case_number = text(1);
+        let image = row
+            .members()
+            .filter_map(|cell| cell.as_str())
+            .find(|cell| cell.starts_with("http") && [".jpg", ".jpeg", ".png"].iter().any(|ext| cell.to_lowercase().ends_with(ext)))
+            .map(String::from);
+        let url = match category {
+            TechTransferCategory::Patent => format!("https://technology.nasa.gov/patent/{}", case_number),
+            TechTransferCategory::Software => format!("https://software.nasa.gov/software/{}", case_number),
+            // Spinoffs carry their own link, fall back to the spinoff site if one can't be found
+            TechTransferCategory::Spinoff => row
+                .members()
+                .filter_map(|cell| cell.as_str())
+                .find(|cell| cell.starts_with("http") && Some(*cell) != image.as_deref())
+                .map_or(String::from("https://spinoff.nasa.gov/"), String::from),
+        };
+        Self {
+            case_number,
+            title: text(2),
+            description: text(3),
+            category: text(5),
+            image,
+            url,
+        }
+    }
+}
+
+// Titles and descriptions come with <span class="highlight"> around the search terms
+fn strip_html(text: &str) -> String {
+    let mut stripped = String::with_capacity(text.len());
+    let mut in_tag = false;
+    for c in text.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' => in_tag = false,
+            c if !in_tag => stripped.push(c),
+            _ => {}
+        }
+    }
+    stripped
+        .replace("&amp;", "&")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&nbsp;", " ")
+        .trim()
+        .to_string()
+}
+
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct TechTransfer {
+    pub category: TechTransferCategory,
+    pub query: String,
+    pub results: Vec<TechTransferResult>,
+    pub total: u32,
+}
+
+impl TechTransfer {
+    #[tracing::instrument(skip_all, fields(category = ?category, query))]
+    pub fn search_blocking(
+        parser: &Parser,
+        category: TechTransferCategory,
+        query: &str,
+    ) -> Result<Self, SpacepixError> {
+        let json_obj = get_json_blocking(parser, &parser.techtransfer_url(category, query))?;
+        Ok(Self::from_json(category, query, &json_obj))
+    }
+
+    fn from_json(category: TechTransferCategory, query: &str, json_obj: &json::JsonValue) -> Self {
+        let results: Vec<TechTransferResult> = json_obj["results"]
+            .members()
+            .map(|row| TechTransferResult::from_row(category, row))
+            .collect();
+        Self {
+            category,
+            query: query.to_string(),
+            total: json_obj["total"].as_u32().unwrap_or(results.len() as u32),
+            results,
+        }
+    }
+}
+
+/**
+ * A two-line element set from the TLE API
+ * date is when the element set was published, line1 and line2 are the TLE itself
+ */
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Tle {
+    pub satellite_id: u32,
+    pub name: String,
+    pub date: String,
+    pub line1: String,
+    pub line2: String,
+}
+
+impl Tle {
+    pub const ISS: u32 = 25544;
+
+    fn from_json(json_obj: &json::JsonValue) -> Self {
+        Self {
+            satellite_id: json_obj["satelliteId"].as_u32().unwrap_or_default(),
+            name: json_obj["name"].as_str().unwrap_or_default().to_string(),
+            date: json_obj["date"].as_str().unwrap_or_default().to_string(),
+            line1: json_obj["line1"].as_str().unwrap_or_default().to_string(),
+            line2: json_obj["line2"].as_str().unwrap_or_default().to_string(),
+        }
+    }
+
+    #[tracing::instrument(skip_all, fields(satellite_id))]
+    pub fn get_tle_blocking(parser: &Parser, satellite_id: u32) -> Result<Self, SpacepixError> {
+        let json_obj = get_json_blocking(parser, &parser.tle_url(satellite_id))?;
+        Ok(Self::from_json(&json_obj))
+    }
+
+    pub fn orbit(&self) -> Result<Orbit, TleError> {
+        Orbit::from_tle(&self.line1, &self.line2)
+    }
+}
+
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct TleSearch {
+    pub search: String,
+    pub page: u32,
+    pub total_items: u32,
+    pub satellites: Vec<Tle>,
+}
+
+impl TleSearch {
+    pub const PAGE_SIZE: u32 = 20;
+
+    #[tracing::instrument(skip_all, fields(search, page))]
+    pub fn search_blocking(parser: &Parser, search: &str, page: u32) -> Result<Self, SpacepixError> {
+        let json_obj = get_json_blocking(parser, &parser.tle_search_url(search, page))?;
+        Ok(Self::from_json(search, page, &json_obj))
+    }
+
+    fn from_json(search: &str, page: u32, json_obj: &json::JsonValue) -> Self {
+        Self {
+            search: search.to_string(),
+            page: page.max(1),
+            total_items: json_obj["totalItems"].as_u32().unwrap_or_default(),
+            satellites: json_obj["member"].members().map(Tle::from_json).collect(),
+        }
+    }
+
+    pub fn page_count(&self) -> u32 {
+        self.total_items.div_ceil(Self::PAGE_SIZE).max(1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[allow(unused_imports)]
+    use super::Apod;
+    use super::{
+        api_key_error, get_text_blocking, redact_api_key, request_log, CaptionCue, EarthAsset,
+        EarthAssets, EarthLocation, EonetEvent, EpicImage, ExoplanetQuery, ExoplanetTable,
+        Exoplanets, Fireballs, HttpClient, HttpSettings, MarsRover, MarsRoverManifest, NEOFeed,
+        NearEarthObject, NIVL, NIVLAsset, NIVLItem, NIVLSearch, ProxySettings, RateLimit,
+        SentryLookup, SentryRecord, TechTransfer, TechTransferCategory, Tle, TleSearch, WSAEnlil,
+        WSAEnlilSimulation,
+    };
+    use crate::errors::{ApiKeyError, SpacepixError};
+    use crate::testing::{http_response, stub_server};
+    use crate::Parser;
+    use std::fs;
+    use std::path::PathBuf;
+    use std::sync::atomic::Ordering;
+    use std::time::{Duration, Instant};
+
+    fn fast_client(max_retries: u32) -> HttpClient {
+        HttpClient::new(HttpSettings {
+            read_timeout_ms: 250,
+            max_retries,
+            initial_backoff_ms: 1,
+            max_backoff_ms: 4,
+            ..Default::default()
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn test_backoff() {
+        let settings = HttpSettings {
+            initial_backoff_ms: 500,
+            max_backoff_ms: 3_000,
+            ..Default::default()
+        };
+        assert_eq!(settings.backoff(0, 0.0), Duration::from_millis(250));
+        assert_eq!(settings.backoff(0, 1.0), Duration::from_millis(500));
+        assert_eq!(settings.backoff(2, 1.0), Duration::from_millis(2_000));
+        assert_eq!(settings.backoff(3, 0.5), Duration::from_millis(2_250));
+        assert_eq!(settings.backoff(40, 2.0), Duration::from_millis(3_000));
+    }
+
+    #[test]
+    fn test_retry_server_errors() {
+        let (url, requests) = stub_server(vec![
+            Some(http_response("503 Service Unavailable", "")),
+            Some(http_response("502 Bad Gateway", "")),
+            Some(http_response("200 OK", "ok")),
+        ]);
+        assert_eq!(fast_client(3).get_blocking(&url).unwrap().text().unwrap(), "ok");
+        assert_eq!(requests.load(Ordering::SeqCst), 3);
+
+        // Out of retries, the last answer comes back
+        let (url, requests) = stub_server(vec![Some(http_response("500 Internal Server Error", "")); 3]);
+        let response = fast_client(2).get_blocking(&url).unwrap();
+        assert_eq!(response.status().as_u16(), 500);
+        assert_eq!(requests.load(Ordering::SeqCst), 3);
+    }
+
+    #[test]
+    fn test_no_retry_client_errors() {
+        let (url, requests) = stub_server(vec![
+            Some(http_response("404 Not Found", "")),
+            Some(http_response("200 OK", "ok")),
+        ]);
+        assert_eq!(fast_client(3).get_blocking(&url).unwrap().status().as_u16(), 404);
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_retry_timeouts() {
+        let (url, requests) = stub_server(vec![None, Some(http_response("200 OK", "ok"))]);
+        assert_eq!(fast_client(1).get_blocking(&url).unwrap().text().unwrap(), "ok");
+        assert_eq!(requests.load(Ordering::SeqCst), 2);
+
+        let (url, _) = stub_server(vec![None]);
+        assert!(matches!(fast_client(0).get_blocking(&url), Err(SpacepixError::Timeout(_))));
+    }
+
+    #[test]
+    fn test_time_budget() {
+        // Each attempt would be allowed a second, but the budget runs out first
+        let (url, requests) = stub_server(vec![None, None, None, None]);
+        let client = HttpClient::new(HttpSettings {
+            read_timeout_ms: 1_000,
+            max_retries: 3,
+            initial_backoff_ms: 1,
+            max_backoff_ms: 4,
+            total_timeout_ms: 300,
+            ..Default::default()
+        })
+        .unwrap();
+        let started = Instant::now();
+        assert!(matches!(client.get_blocking(&url), Err(SpacepixError::Timeout(_))));
+        assert!(started.elapsed() < Duration::from_millis(900));
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_proxy() {
+        // The .invalid host can't resolve, so an answer means the request went through the proxy
+        let (proxy, requests) = stub_server(vec![Some(http_response("200 OK", "proxied"))]);
+        let client = HttpClient::new(HttpSettings {
+            proxy: ProxySettings {
+                http_proxy: proxy.clone(),
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .unwrap();
+        let response = client.get_blocking("http://api.nasa.invalid/planetary/apod").unwrap();
+        assert_eq!(response.text().unwrap(), "proxied");
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+
+        // Hosts on the no proxy list go direct, the proxy here would refuse the connection
+        let (url, requests) = stub_server(vec![Some(http_response("200 OK", "direct"))]);
+        let client = HttpClient::new(HttpSettings {
+            max_retries: 0,
+            proxy: ProxySettings {
+                http_proxy: String::from("http://127.0.0.1:9"),
+                no_proxy: String::from("localhost, 127.0.0.1"),
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .unwrap();
+        assert_eq!(client.get_blocking(&url).unwrap().text().unwrap(), "direct");
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_proxy_settings() {
+        let saved = ProxySettings {
+            http_proxy: String::from("http://proxy.lab:3128"),
+            https_proxy: String::from("http://proxy.lab:3128"),
+            no_proxy: String::from("localhost"),
+            ca_certificates: vec![PathBuf::from("/etc/ssl/lab.pem")],
+            ..Default::default()
+        };
+        let flags = ProxySettings {
+            https_proxy: String::from("http://other.lab:8080"),
+            socks_proxy: String::from("socks5h://127.0.0.1:1080"),
+            ..Default::default()
+        };
+        let merged = saved.overridden_by(&flags);
+        assert_eq!(merged.http_proxy, "http://proxy.lab:3128");
+        assert_eq!(merged.https_proxy, "http://other.lab:8080");
+        assert_eq!(merged.socks_proxy, "socks5h://127.0.0.1:1080");
+        assert_eq!(merged.no_proxy, "localhost");
+        assert_eq!(merged.ca_certificates, vec![PathBuf::from("/etc/ssl/lab.pem")]);
+        assert!(ProxySettings::default().is_empty());
+        assert!(!merged.is_empty());
+
+        let merged = ProxySettings {
+            ca_certificates: Vec::new(),
+            ..merged
+        };
+        assert_eq!(merged.proxies().unwrap().len(), 3);
+
+        let bad = ProxySettings {
+            https_proxy: String::from("not a proxy"),
+            ..Default::default()
+        };
+        assert!(matches!(bad.proxies(), Err(SpacepixError::Proxy { url, .. }) if url == "not a proxy"));
+    }
+
+    #[test]
+    fn test_certificates() {
+        let dir = std::env::temp_dir().join(format!("spacepix-certificates-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let missing = ProxySettings {
+            ca_certificates: vec![dir.join("missing.pem")],
+            ..Default::default()
+        };
+        assert!(matches!(missing.certificates(), Err(SpacepixError::CertificateFile { .. })));
+
+        let empty = dir.join("empty.pem");
+        fs::write(&empty, "-----BEGIN NOTHING-----\n-----END NOTHING-----\n").unwrap();
+        let garbage = dir.join("garbage.der");
+        fs::write(&garbage, "not a certificate").unwrap();
+        for path in [empty, garbage] {
+            let settings = ProxySettings {
+                ca_certificates: vec![path.clone()],
+                ..Default::default()
+            };
+            assert!(matches!(settings.certificates(), Err(SpacepixError::Certificate { path: p, .. }) if p == path));
+        }
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_redact_api_key() {
+        assert_eq!(
+            redact_api_key("https://api.nasa.gov/planetary/apod?api_key=abc123"),
+            "https://api.nasa.gov/planetary/apod?api_key=REDACTED"
+        );
+        assert_eq!(
+            redact_api_key("https://api.nasa.gov/neo/rest/v1/feed?start_date=2024-10-10&api_key=abc123&end_date=2024-10-11"),
+            "https://api.nasa.gov/neo/rest/v1/feed?start_date=2024-10-10&api_key=REDACTED&end_date=2024-10-11"
+        );
+        assert_eq!(
+            redact_api_key("error sending request for url (https://api.nasa.gov/planetary/apod?api_key=abc123): timed out"),
+            "error sending request for url (https://api.nasa.gov/planetary/apod?api_key=REDACTED): timed out"
+        );
+        assert_eq!(
+            redact_api_key("https://images-api.nasa.gov/search?q=moon"),
+            "https://images-api.nasa.gov/search?q=moon"
+        );
+    }
+
+    #[test]
+    fn test_request_log() {
+        let body = r#"{"title": "Aurora"}"#;
+        let response = format!(
+            "HTTP/1.1 200 OK\r\nX-Cache: Hit from cloudfront\r\nX-RateLimit-Remaining: 39\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            body.len(),
+            body
+        );
+        let (url, _) = stub_server(vec![Some(response)]);
+        get_text_blocking(&format!("{}planetary/apod?api_key=SECRET", url)).unwrap();
+
+        // Other tests share the log, the stub's port picks out this one
+        let redacted = format!("{}planetary/apod?api_key=REDACTED", url);
+        let records = request_log();
+        let record = records.iter().find(|record| record.url == redacted).unwrap();
+        assert_eq!(record.status, Some(200));
+        assert_eq!(record.cache, Some(true));
+        assert_eq!(record.remaining, Some(39));
+        assert_eq!(record.bytes, Some(body.len() as u64));
+        assert_eq!(record.body.as_deref(), Some(body));
+        assert_eq!(record.retries, 0);
+        assert!(records.iter().all(|record| !record.url.contains("SECRET")));
+
+        let (url, _) = stub_server(vec![None]);
+        assert!(fast_client(0).get_blocking(&format!("{}?api_key=SECRET", url)).is_err());
+        let redacted = format!("{}?api_key=REDACTED", url);
+        let records = request_log();
+        let record = records.iter().find(|record| record.url == redacted).unwrap();
+        assert_eq!(record.status, None);
+        let error = record.error.as_deref().unwrap();
+        assert!(error.contains("api_key=REDACTED"));
+        assert!(!error.contains("SECRET"));
+    }
+
+    #[test]
+    fn test_get_apod_data_blocking() {
+        let (url, _) = stub_server(vec![Some(http_response(
+            "200 OK",
+            r#"{
+                "date": "2024-10-10",
+                "explanation": "A sky full of aurora.",
+                "hdurl": "https://apod.nasa.gov/apod/image/2410/aurora.jpg",
+                "media_type": "image",
+                "service_version": "v1",
+                "title": "Aurora over the Lake",
+                "url": "https://apod.nasa.gov/apod/image/2410/aurora1024.jpg"
+            }"#,
+        ))]);
+        let mut parser = Parser::new(String::from("DEMO_KEY"));
+        parser.urls.apod = format!("{}planetary/apod?api_key=", url);
+
+        let apod = Apod::get_apod_data_blocking(&parser).unwrap();
+        assert_eq!(apod.title, "Aurora over the Lake");
+        assert_eq!(apod.date, "2024-10-10");
+        assert_eq!(apod.hdurl, "https://apod.nasa.gov/apod/image/2410/aurora.jpg");
+        assert_eq!(parser.rate_limit("Default").requests, 1);
+    }
+
+    #[test]
+    fn test_get_neows_feed_blocking() {
+        // An object without sizes, an epoch or the Sentry flag is still listed
+        let (url, _) = stub_server(vec![Some(http_response(
+            "200 OK",
+            r#"{
+                "links": {"self": "http://api.nasa.gov/neo/rest/v1/feed?start_date=2024-10-10"},
+                "near_earth_objects": {"2024-10-10": [{
+                    "id": "3542519",
+                    "name": "(2010 PK9)",
+                    "close_approach_data": [{"close_approach_date": "2024-10-10", "orbiting_body": "Earth"}]
+                }]}
+            }"#,
+        ))]);
+        let mut parser = Parser::new(String::from("DEMO_KEY"));
+        parser.urls.neows = format!("{}neo/rest/v1/feed?start_date=START_DATE&end_date=END_DATE&api_key=", url);
+
+        let mut feed = NEOFeed::default();
+        let objects = &feed.get_neows_feed_blocking(&parser, "2024-10-10").unwrap().near_earth_objects;
+        assert_eq!(objects.len(), 1);
+        assert_eq!(objects[0].name, "(2010 PK9)");
+        assert_eq!(objects[0].estimated_diameter, ((0.0, 0.0), (0.0, 0.0)));
+        assert_eq!(objects[0].epoch_date_close_approach, 0);
+        assert!(!objects[0].is_sentry_object);
+        assert_eq!(objects[0].is_potentially_hazardous_asteroid, None);
+    }
+
+    #[test]
+    fn test_wsa_enlil_simulation_targets() {
+        let json_obj = json::parse(
+            r#"[{
+                "simulationID": "WSA-ENLIL/27393/1",
+                "modelCompletionTime": "2024-01-02T12:00Z",
+                "au": 2.0,
+                "cmeInputs": [{"cmeid": "2024-01-01T00:00:00-CME-001", "speed": 650.0, "isMostAccurate": true}],
+                "estimatedShockArrivalTime": "2024-01-04T06:00Z",
+                "estimatedDuration": 12.5,
+                "isEarthGB": true,
+                "kp_90": 5,
+                "kp_180": null,
+                "impactList": [{"isGlancingBlow": false, "location": "STEREO A", "arrivalTime": "2024-01-03T20:00Z"}],
+                "link": "https://webtools.ccmc.gsfc.nasa.gov/DONKI/view/WSA-ENLIL/27393/-1"
+            }]"#,
+        )
+        .unwrap();
+        let enlil = WSAEnlil {
+            simulations: json_obj.members().map(WSAEnlilSimulation::from_json).collect(),
+        };
+        let simulation = &enlil.simulations[0];
+        let targets = simulation.targets();
+
+        assert_eq!(targets.len(), 2);
+        assert_eq!(targets[0].location, "Earth");
+        assert!(targets[0].is_glancing_blow);
+        assert_eq!(targets[1].location, "STEREO A");
+        assert_eq!(simulation.kp, (None, Some(5.0), None, None));
+        assert_eq!(enlil.simulations_for_cme("2024-01-01T00:00:00-CME-001").len(), 1);
+        assert!(enlil.simulations_for_cme("2024-01-05T00:00:00-CME-001").is_empty());
+    }
+
+    #[test]
+    fn test_nivl_search_results() {
+        let json_obj = json::parse(
+            r#"{"collection": {
+                "items": [
+                    {
+                        "href": "https://images-assets.nasa.gov/image/as11-40-5874/collection.json",
+                        "data": [{
+                            "nasa_id": "as11-40-5874",
+                            "title": "Apollo 11 Mission image - Astronaut Edwin Aldrin poses beside th",
+                            "media_type": "image",
+                            "center": "JSC",
+                            "date_created": "1969-07-20T00:00:00Z",
+                            "keywords": ["APOLLO 11 FLIGHT", "MOON"]
+                        }],
+                        "links": [{"href": "https://images-assets.nasa.gov/image/as11-40-5874/as11-40-5874~thumb.jpg", "rel": "preview", "render": "image"}]
+                    },
+                    {
+                        "href": "https://images-assets.nasa.gov/audio/Apollo11Highlights/collection.json",
+                        "data": [{"nasa_id": "Apollo11Highlights", "title": "Apollo 11 Highlights", "media_type": "audio"}]
+                    }
+                ],
+                "metadata": {"total_hits": 12345}
+            }}"#,
+        )
+        .unwrap();
+        let nivl = NIVL::from_json(&json_obj, &NIVLSearch::default());
+
+        assert_eq!(nivl.items.len(), 2);
+        assert_eq!(nivl.items[0].keywords, vec!["APOLLO 11 FLIGHT", "MOON"]);
+        assert_eq!(
+            nivl.items[0].thumbnail.as_deref(),
+            Some("https://images-assets.nasa.gov/image/as11-40-5874/as11-40-5874~thumb.jpg")
+        );
+        assert_eq!(nivl.items[1].thumbnail, None);
+        assert_eq!(nivl.total_hits, 12345);
+        assert_eq!(nivl.page_count(), 417); // Capped at 10,000 hits / 24 per page
+    }
+
+    #[test]
+    fn test_nivl_asset_renditions() {
+        let asset_json = json::parse(
+            r#"{"collection": {"items": [
+                {"href": "http://images-assets.nasa.gov/image/as11-40-5874/as11-40-5874~orig.jpg"},
+                {"href": "http://images-assets.nasa.gov/image/as11-40-5874/as11-40-5874~medium.jpg"},
+                {"href": "http://images-assets.nasa.gov/image/as11-40-5874/as11-40-5874~thumb.jpg"},
+                {"href": "http://images-assets.nasa.gov/image/as11-40-5874/metadata.json"}
+            ]}}"#,
+        )
+        .unwrap();
+        let item = NIVLItem {
+            nasa_id: String::from("as11-40-5874"),
+            description: String::from("Truncated"),
+            ..Default::default()
+        };
+        let mut asset = NIVLAsset::from_json(&asset_json, &item);
+        asset.apply_metadata(
+            &json::parse(
+                r#"{
+                    "AVAIL:Description": "Astronaut Edwin E. Aldrin Jr., lunar module pilot, poses for a photograph beside the deployed United States flag.",
+                    "AVAIL:Photographer": "Neil A. Armstrong",
+                    "AVAIL:Keywords": ["APOLLO 11 FLIGHT", "FLAGS"],
+                    "AVAIL:Title": ""
+                }"#,
+            )
+            .unwrap(),
+        );
+
+        let names: Vec<&str> = asset.renditions.iter().map(|r| r.name.as_str()).collect();
+        assert_eq!(names, vec!["orig", "medium", "thumb"]);
+        assert!(asset.rendition("orig").unwrap().href.ends_with("~orig.jpg"));
+        assert!(asset.item.description.starts_with("Astronaut Edwin E. Aldrin Jr."));
+        assert_eq!(asset.item.photographer, "Neil A. Armstrong");
+        assert_eq!(asset.item.keywords, vec!["APOLLO 11 FLIGHT", "FLAGS"]);
+    }
+
+    #[test]
+    fn test_nivl_video_asset() {
+        let asset_json = json::parse(
+            r#"{"collection": {"items": [
+                {"href": "http://images-assets.nasa.gov/video/Apollo11Launch/Apollo11Launch~mobile.mp4"},
+                {"href": "http://images-assets.nasa.gov/video/Apollo11Launch/Apollo11Launch~orig.mp4"},
+                {"href": "http://images-assets.nasa.gov/video/Apollo11Launch/Apollo11Launch.srt"},
+                {"href": "http://images-assets.nasa.gov/video/Apollo11Launch/Apollo11Launch~thumb.jpg"}
+            ]}}"#,
+        )
+        .unwrap();
+        let mut asset = NIVLAsset::from_json(&asset_json, &NIVLItem::default());
+        asset.apply_metadata(&json::parse(r#"{"QuickTime:Duration": "0:02:13"}"#).unwrap());
+
+        assert_eq!(asset.playable().unwrap().name, "orig");
+        assert_eq!(asset.caption_files().count(), 1);
+        assert_eq!(asset.duration.as_deref(), Some("0:02:13"));
+    }
+
+    #[test]
+    fn test_parse_captions() {
+        let srt = "1\r\n00:00:01,000 --> 00:00:04,500\r\nLiftoff!\r\n\r\n2\r\n00:00:05,000 --> 00:00:07,000\r\nWe have cleared\r\nthe tower.\r\n";
+        let vtt = "WEBVTT\n\n00:01.000 --> 00:04.500 align:start\nLiftoff!\n";
+
+        assert_eq!(
+            CaptionCue::parse(srt),
+            vec![
+                CaptionCue {
+                    start: String::from("00:00:01.000"),
+                    end: String::from("00:00:04.500"),
+                    text: String::from("Liftoff!"),
+                },
+                CaptionCue {
+                    start: String::from("00:00:05.000"),
+                    end: String::from("00:00:07.000"),
+                    text: String::from("We have cleared\nthe tower."),
+                },
+            ]
+        );
+        assert_eq!(CaptionCue::parse(vtt)[0].end, "00:04.500");
+        assert_eq!(CaptionCue::parse(vtt).len(), 1);
+    }
+
+    #[test]
+    fn test_mars_rover_manifest() {
+        let json_obj = json::parse(
+            r#"{"photo_manifest": {
+                "name": "Spirit",
+                "landing_date": "2004-01-04",
+                "launch_date": "2003-06-10",
+                "status": "complete",
+                "max_sol": 2208,
+                "max_date": "2010-03-21",
+                "total_photos": 124550,
+                "photos": [
+                    {"sol": 1, "earth_date": "2004-01-05", "total_photos": 77, "cameras": ["ENTRY", "FHAZ", "NAVCAM", "PANCAM", "RHAZ"]},
+                    {"sol": 3, "earth_date": "2004-01-07", "total_photos": 125, "cameras": ["FHAZ", "NAVCAM"]}
+                ]
+            }}"#,
+        )
+        .unwrap();
+        let manifest = MarsRoverManifest::from_json(&json_obj, MarsRover::Spirit);
+
+        assert_eq!(manifest.max_sol, 2208);
+        assert_eq!(manifest.status, "complete");
+        assert_eq!(manifest.sol(3).unwrap().cameras, vec!["FHAZ", "NAVCAM"]);
+        assert!(manifest.sol(2).is_none());
+    }
+
+    #[test]
+    fn test_epic_image() {
+        let json_obj = json::parse(
+            r#"{
+                "identifier": "20190530011359",
+                "caption": "This image was taken by NASA's EPIC camera onboard the NOAA DSCOVR spacecraft",
+                "image": "epic_1b_20190530011359",
+                "centroid_coordinates": {"lat": 17.13, "lon": 166.92},
+                "dscovr_j2000_position": {"x": 3.0, "y": 4.0, "z": 12.0},
+                "date": "2019-05-30 00:50:27"
+            }"#,
+        )
+        .unwrap();
+        let image = EpicImage::from_json(&json_obj);
+
+        assert_eq!(image.centroid, (17.13, 166.92));
+        assert_eq!(image.date_path(), "2019/05/30");
+        assert_eq!(EpicImage::distance(image.dscovr_position), 13.0);
+        assert_eq!(image.sun_position, (0.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn test_eonet_event() {
+        let json_obj = json::parse(
+            r#"{
+                "id": "EONET_6543",
+                "title": "Tropical Storm Example",
+                "link": "https://eonet.gsfc.nasa.gov/api/v3/events/EONET_6543",
+                "closed": null,
+                "categories": [{"id": "severeStorms", "title": "Severe Storms"}],
+                "sources": [{"id": "JTWC", "url": "https://www.metoc.navy.mil/jtwc/products/wp0124.tcw"}],
+                "geometry": [
+                    {"magnitudeValue": 35.0, "magnitudeUnit": "kts", "date": "2024-06-01T00:00:00Z", "type": "Point", "coordinates": [133.5, 12.1]},
+                    {"magnitudeValue": null, "date": "2024-06-02T00:00:00Z", "type": "Polygon", "coordinates": [[[-120.0, 35.0], [-119.0, 35.0], [-119.0, 36.0], [-120.0, 35.0]]]}
+                ]
+            }"#,
+        )
+        .unwrap();
+        let event = EonetEvent::from_json(&json_obj);
+
+        assert_eq!(event.closed, None);
+        assert_eq!(event.categories[0].id, "severeStorms");
+        assert_eq!(event.geometry[0].coordinates, vec![(133.5, 12.1)]);
+        assert_eq!(event.geometry[0].magnitude, Some((35.0, String::from("kts"))));
+        assert_eq!(event.geometry[1].coordinates.len(), 4);
+        assert_eq!(event.geometry[1].magnitude, None);
+    }
+
+    #[test]
+    fn test_sentry_record() {
+        let json_obj = json::parse(
+            r#"{
+                "summary": {"des": "29075", "fullname": "29075 (1950 DA)", "ip": "3.8e-4", "ps_cum": "-0.93", "ps_max": "-0.93", "ts_max": null, "n_imp": 1, "diameter": "1.3", "last_obs": "2021-12-01"},
+                "data": [{"date": "2880-03-16.99", "ip": "3.8e-4", "ps": "-0.93", "ts": "0", "energy": "7.3e+04"}]
+            }"#,
+        )
+        .unwrap();
+        let record = SentryRecord::from_json(&json_obj, &SentryLookup::Des(String::from("29075"))).unwrap();
+
+        assert_eq!(record.object.fullname, "29075 (1950 DA)");
+        assert_eq!(record.object.impact_probability, 3.8e-4);
+        assert_eq!(record.object.palermo_scale, (-0.93, -0.93));
+        assert_eq!(record.object.torino_scale, None);
+        assert_eq!(record.object.diameter, Some(1.3));
+        assert_eq!(record.impacts[0].torino_scale, Some(0));
+        assert_eq!(record.impacts[0].energy, Some(7.3e4));
+    }
+
+    #[test]
+    fn test_sentry_record_missing() {
+        // NeoWs can still flag an object Sentry has since dropped, Sentry says so with HTTP 200
+        let lookup = SentryLookup::Des(String::from("2008 JL3"));
+        let removed = json::parse(
+            r#"{"signature": {"source": "NASA/JPL Sentry Data API", "version": "2.0"}, "error": "specified object removed", "removed": "2023-02-07 14:18:26"}"#,
+        )
+        .unwrap();
+        let e = SentryRecord::from_json(&removed, &lookup).unwrap_err();
+        assert!(matches!(e, SpacepixError::NotFound(_)));
+        assert_eq!(e.to_string(), "2008 JL3 was removed from Sentry on 2023-02-07 14:18:26");
+
+        let unknown = json::parse(r#"{"error": "specified object not found"}"#).unwrap();
+        let e = SentryRecord::from_json(&unknown, &lookup).unwrap_err();
+        assert_eq!(e.to_string(), "2008 JL3 isn't on Sentry's risk list (specified object not found)");
+        assert!(!e.is_retryable());
+    }
+
+    #[test]
+    fn test_fireballs() {
+        let json_obj = json::parse(
+            r#"{
+                "count": "2",
+                "fields": ["date", "energy", "impact-e", "lat", "lat-dir", "lon", "lon-dir", "alt", "vel"],
+                "data": [
+                    ["2024-05-18 22:46:46", "18.6", "0.48", "40.6", "N", "8.6", "W", "30.0", "40.4"],
+                    ["2024-04-30 06:12:08", "3.2", "0.11", null, null, null, null, null, null]
+                ]
+            }"#,
+        )
+        .unwrap();
+        let fireballs = Fireballs::from_json(&json_obj);
+
+        assert_eq!(fireballs.events.len(), 2);
+        assert_eq!(fireballs.events[0].lat, Some(40.6));
+        assert_eq!(fireballs.events[0].lon, Some(-8.6));
+        assert_eq!(fireballs.events[0].impact_energy, 0.48);
+        assert_eq!(fireballs.events[1].lat, None);
+        assert_eq!(fireballs.events[1].velocity, None);
+    }
+
+    #[test]
+    fn test_cad_feed() {
+        let json_obj = json::parse(
+            r#"{
+                "count": "1",
+                "fields": ["des", "orbit_id", "jd", "cd", "dist", "dist_min", "dist_max", "v_rel", "v_inf", "t_sigma_f", "h", "fullname"],
+                "data": [["99942", "220", "2462240.407091595", "2029-Apr-13 21:46", "0.000254099", "0.000254099", "0.000254099", "7.42", "5.84", "< 00:01", "19.09", "    99942 Apophis (2004 MN4)"]]
+            }"#,
+        )
+        .unwrap();
+        let mut feed = NEOFeed::default();
+        feed.set_cad_objects(&json_obj);
+        let apophis = &feed.near_earth_objects[0];
+
+        assert_eq!(feed.element_count, 1);
+        assert_eq!(apophis.name, "99942 Apophis (2004 MN4)");
+        assert_eq!(apophis.close_approach_date, "2029-04-13");
+        assert_eq!(apophis.epoch_date_close_approach, 1870811160000);
+        assert_eq!(apophis.relative_velocity.0, "7.42");
+        assert!((apophis.miss_distance.1.parse::<f64>().unwrap() - 0.0989).abs() < 0.001);
+        // 404 to 904 meters across for H = 19.09
+        assert!((apophis.estimated_diameter.1 .0 - 404.2).abs() < 0.5);
+        assert!((apophis.estimated_diameter.1 .1 - 903.7).abs() < 0.5);
+        assert_eq!(apophis.is_potentially_hazardous_asteroid, None);
+
+        // Saved state from before CAD results has a plain bool
+        let mut saved = serde_json::to_value(apophis).unwrap();
+        saved["is_potentially_hazardous_asteroid"] = serde_json::Value::Bool(true);
+        let loaded: NearEarthObject = serde_json::from_value(saved).unwrap();
+        assert_eq!(loaded.is_potentially_hazardous_asteroid, Some(true));
+        let round_trip = serde_json::to_string(apophis).unwrap();
+        let loaded: NearEarthObject = serde_json::from_str(&round_trip).unwrap();
+        assert_eq!(loaded.is_potentially_hazardous_asteroid, None);
+    }
+
+    #[test]
+    fn test_exoplanet_adql() {
+        let query = ExoplanetQuery {
+            table: ExoplanetTable::Ps,
+            discovery_method: String::from("Transit"),
+            year_min: String::from("2020"),
+            radius_max: String::from("1.5"),
+            period_min: String::from("not a number"),
+            limit: 10,
+            ..Default::default()
+        };
+        assert_eq!(
+            query.adql(),
+            "select top 10 pl_name,hostname,discoverymethod,disc_year,pl_rade,pl_bmasse,pl_orbper from ps where default_flag=1 and discoverymethod='Transit' and disc_year>=2020 and pl_rade<=1.5 order by disc_year desc"
+        );
+
+        let quoted = ExoplanetQuery {
+            discovery_method: String::from("x' or 1=1 --"),
+            ..Default::default()
+        };
+        assert!(quoted.adql().contains("discoverymethod='x'' or 1=1 --'"));
+        assert!(ExoplanetQuery::default().adql().contains(" from pscomppars order by"));
+    }
+
+    #[test]
+    fn test_exoplanets() {
+        let json_obj = json::parse(
+            r#"[
+                {"pl_name": "TRAPPIST-1 e", "hostname": "TRAPPIST-1", "discoverymethod": "Transit", "disc_year": 2017, "pl_rade": 0.92, "pl_bmasse": 0.692, "pl_orbper": 6.101013},
+                {"pl_name": "HD 100546 b", "hostname": "HD 100546", "discoverymethod": "Imaging", "disc_year": 2013, "pl_rade": null, "pl_bmasse": null, "pl_orbper": null}
+            ]"#,
+        )
+        .unwrap();
+        let exoplanets = Exoplanets::from_json(&json_obj);
+
+        assert_eq!(exoplanets.planets.len(), 2);
+        assert_eq!(exoplanets.planets[0].name, "TRAPPIST-1 e");
+        assert_eq!(exoplanets.planets[0].discovery_year, Some(2017));
+        assert_eq!(exoplanets.planets[0].period, Some(6.101013));
+        assert_eq!(exoplanets.planets[1].radius, None);
+    }
+
+    #[test]
+    fn test_earth_assets() {
+        assert_eq!(
+            EarthAssets::sample_dates("2020-01-01", "2020-01-31", 4),
+            Some(vec![
+                String::from("2020-01-01"),
+                String::from("2020-01-11"),
+                String::from("2020-01-21"),
+                String::from("2020-01-31"),
+            ])
+        );
+        assert_eq!(EarthAssets::sample_dates("2020-01-01", "2020-01-01", 3), Some(vec![String::from("2020-01-01")]));
+        assert_eq!(EarthAssets::sample_dates("January", "2020-01-01", 3), None);
+
+        let mut earth_assets = EarthAssets::default();
+        for asset in [
+            r#"{"date": "2020-01-12T16:48:21.780000", "id": "LANDSAT/LC08/C01/T1_SR/LC08_025039_20200112", "url": "https://earthengine.googleapis.com/"}"#,
+            r#"{"date": "2020-01-03T16:54:32.120000", "id": "LANDSAT/LC08/C01/T1_SR/LC08_026039_20200103", "url": "https://earthengine.googleapis.com/"}"#,
+            r#"{"date": "2020-01-12T16:48:21.780000", "id": "LANDSAT/LC08/C01/T1_SR/LC08_025039_20200112", "url": "https://earthengine.googleapis.com/"}"#,
+        ] {
+            earth_assets.add_asset(&json::parse(asset).unwrap());
+        }
+
+        assert_eq!(earth_assets.assets.len(), 2);
+        assert_eq!(earth_assets.assets[0].day(), "2020-01-03");
+        assert_eq!(earth_assets.assets[1].day(), "2020-01-12");
+    }
+
+    #[test]
+    fn test_get_assets_blocking() {
+        // A rejected key would fail every date, so the first answer is the only request
+        let (url, requests) = stub_server(vec![
+            Some(http_response("403 Forbidden", r#"{"error": {"code": "API_KEY_INVALID"}}"#)),
+            Some(http_response("403 Forbidden", r#"{"error": {"code": "API_KEY_INVALID"}}"#)),
+        ]);
+        let mut parser = Parser::new(String::from("BAD_KEY"));
+        parser.urls.earth_assets = format!("{}planetary/earth/assets?QUERY&api_key=", url);
+
+        let dates = EarthAssets::sample_dates("2020-01-01", "2020-01-31", 4).unwrap();
+        let location = EarthLocation::default();
+        assert!(EarthAssets::get_assets_blocking(&parser, &location, &dates).is_err());
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_earth_image_blocking() {
+        // Imagery goes through the shared client, so it counts against the key like any other request
+        let (url, _) = stub_server(vec![Some(http_response("200 OK", "PNG"))]);
+        let mut parser = Parser::new(String::from("DEMO_KEY"));
+        parser.urls.earth_imagery = format!("{}planetary/earth/imagery?QUERY&api_key=", url);
+
+        let asset = EarthAsset {
+            date: String::from("2020-01-12T16:48:21.780000"),
+            ..Default::default()
+        };
+        assert_eq!(asset.get_image_blocking(&parser, &EarthLocation::default()).unwrap(), b"PNG");
+        assert_eq!(parser.rate_limit("Default").requests, 1);
+        assert!(request_log().iter().any(|record| record.url.ends_with("date=2020-01-12&dim=0.1&api_key=REDACTED")));
+    }
+
+    #[test]
+    fn test_techtransfer() {
+        let json_obj = json::parse(
+            r#"{
+                "results": [
+                    ["59f4d9ed0b8a2b1c6da2f3c1", "LEW-TOPS-99", "Low-Cost, High-Performance <span class=\"highlight\">Engine</span> Seals", "Seals that last longer in hot &amp; harsh conditions.", "LEW-18254-1", "Aeronautics", "", "", "", "https://technology.nasa.gov/t2media/tops/img/LEW-TOPS-99/front.jpg", "1", 1.74]
+                ],
+                "count": 1,
+                "total": 12,
+                "perpage": 10,
+                "page": 0
+            }"#,
+        )
+        .unwrap();
+        let patents = TechTransfer::from_json(TechTransferCategory::Patent, "engine", &json_obj);
+        let seal = &patents.results[0];
+
+        assert_eq!(patents.total, 12);
+        assert_eq!(seal.title, "Low-Cost, High-Performance Engine Seals");
+        assert_eq!(seal.description, "Seals that last longer in hot & harsh conditions.");
+        assert_eq!(seal.category, "Aeronautics");
+        assert_eq!(seal.image.as_deref(), Some("https://technology.nasa.gov/t2media/tops/img/LEW-TOPS-99/front.jpg"));
+        assert_eq!(seal.url, "https://technology.nasa.gov/patent/LEW-TOPS-99");
+
+        let spinoffs = TechTransfer::from_json(TechTransferCategory::Spinoff, "engine", &json_obj);
+        assert_eq!(spinoffs.results[0].url, "https://spinoff.nasa.gov/");
+    }
+
+    #[test]
+    fn test_tle_search() {
+        let json_obj = json::parse(
+            r#"{
+                "@context": "https://www.w3.org/ns/hydra/context.jsonld",
+                "@id": "https://tle.ivanstanojevic.me/api/tle",
+                "@type": "Collection",
+                "totalItems": 41,
+                "member": [
+                    {
+                        "@id": "https://tle.ivanstanojevic.me/api/tle/25544",
+                        "@type": "Tle",
+                        "satelliteId": 25544,
+                        "name": "ISS (ZARYA)",
+                        "date": "2008-09-20T12:25:40+00:00",
+                        "line1": "1 25544U 98067A   08264.51782528 -.00002182  00000-0 -11606-4 0  2927",
+                        "line2": "2 25544  51.6416 247.4627 0006703 130.5360 325.0288 15.72125391563537"
+                    }
+                ],
+                "parameters": {"search": "ISS", "sort": "popularity", "sort-dir": "desc", "page": 1, "page-size": 20}
+            }"#,
+        )
+        .unwrap();
+        let search = TleSearch::from_json("ISS", 1, &json_obj);
+        let iss = &search.satellites[0];
+
+        assert_eq!(search.page_count(), 3);
+        assert_eq!(iss.satellite_id, Tle::ISS);
+        assert_eq!(iss.name, "ISS (ZARYA)");
+        assert!((iss.orbit().unwrap().period_minutes() - 91.6).abs() < 0.1);
+    }
+
+    #[test]
+    fn test_api_key_error() {
+        let invalid = r#"{"error":{"code":"API_KEY_INVALID","message":"An invalid api_key was supplied. Get one at https://api.nasa.gov:443"}}"#;
+        assert!(matches!(api_key_error(403, invalid), Some(ApiKeyError::InvalidApiKey())));
+        assert!(api_key_error(403, r#"{"error":{"code":"API_KEY_MISSING"}}"#).is_some());
+        assert!(api_key_error(429, r#"{"error":{"code":"OVER_RATE_LIMIT"}}"#).is_none());
+        assert!(api_key_error(200, invalid).is_none());
+    }
+
+    #[test]
+    fn test_rate_limit() {
+        let mut headers = reqwest::header::HeaderMap::new();
+        headers.insert("X-RateLimit-Limit", "1000".parse().unwrap());
+        headers.insert("X-RateLimit-Remaining", "997".parse().unwrap());
+        let mut rate_limit = RateLimit::default();
+        rate_limit.record(&headers);
+        // JPL and other hosts don't send the headers, keep what NASA said last
+        rate_limit.record(&reqwest::header::HeaderMap::new());
+        assert_eq!(rate_limit, RateLimit { requests: 2, limit: Some(1000), remaining: Some(997) });
     }
 }
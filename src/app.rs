@@ -1,8 +1,15 @@
-use crate::ui::{AboutWindow, ApiKeyWindow};
-use crate::{Apod, ApodWindow, NEOFeed, NIVLWindow, NeowsWindow, Parser, NIVL};
+use crate::errors::ApiKeyError;
+use crate::ui::{AboutWindow, ApiKeyWindow, ErrorPanel, ErrorSource};
+use crate::{
+    configure_http_client, Apod, ApodWindow, ConfigDirs, EarthAssets, Eonet, EonetWindow,
+    EpicDay, EpicWindow, ExoplanetWindow, Exoplanets, FireballWindow, Fireballs, LandsatWindow,
+    MarsRoverManifest, MarsRoverPhotos, MarsRoverWindow, NEOFeed, NIVL, NIVLWindow, NeoSource,
+    NeowsWindow, NetworkInspectorWindow, NetworkWindow, Parser, ProfilesWindow, ProxySettings,
+    Sentry, SentryLookup, SentryWindow, TechTransfer, TechTransferWindow, TleSearch, TleWindow,
+    WSAEnlil, WSAEnlilWindow, WelcomeWindow,
+};
 use eframe::egui::{FontId, RichText};
 use egui::vec2;
-use std::path;
 
 // This is the object that the view port will represent
 /// We derive Deserialize/Serialize so we can persist app state on shutdown.
@@ -13,11 +20,37 @@ pub struct SpacePixUi {
     apod: Option<Apod>,
     neows: Option<NEOFeed>,
     nivl: Option<NIVL>,
+    wsa_enlil: Option<WSAEnlil>,
+    mars_photos: Option<MarsRoverPhotos>,
+    mars_manifest: Option<MarsRoverManifest>,
+    epic: Option<EpicDay>,
+    eonet: Option<Eonet>,
+    sentry: Option<Sentry>,
+    fireballs: Option<Fireballs>,
+    exoplanets: Option<Exoplanets>,
+    landsat: Option<EarthAssets>,
+    techtransfer: Option<TechTransfer>,
+    tle_search: Option<TleSearch>,
     apod_ui: ApodWindow,
     neows_ui: NeowsWindow,
     nivl_ui: NIVLWindow,
+    wsa_enlil_ui: WSAEnlilWindow,
+    mars_rover_ui: MarsRoverWindow,
+    epic_ui: EpicWindow,
+    eonet_ui: EonetWindow,
+    sentry_ui: SentryWindow,
+    fireball_ui: FireballWindow,
+    exoplanet_ui: ExoplanetWindow,
+    landsat_ui: LandsatWindow,
+    techtransfer_ui: TechTransferWindow,
+    tle_ui: TleWindow,
     about: AboutWindow,
+    welcome: WelcomeWindow,
     api: ApiKeyWindow,
+    profiles_ui: ProfilesWindow,
+    network_ui: NetworkWindow,
+    inspector_ui: NetworkInspectorWindow,
+    errors: ErrorPanel,
     parser: Parser,
 }
 
@@ -27,34 +60,88 @@ impl Default for SpacePixUi {
             apod: None,
             neows: None,
             nivl: None,
+            wsa_enlil: None,
+            mars_photos: None,
+            mars_manifest: None,
+            epic: None,
+            eonet: None,
+            sentry: None,
+            fireballs: None,
+            exoplanets: None,
+            landsat: None,
+            techtransfer: None,
+            tle_search: None,
             apod_ui: ApodWindow::default(),
             neows_ui: NeowsWindow::default(),
             nivl_ui: NIVLWindow::default(),
+            wsa_enlil_ui: WSAEnlilWindow::default(),
+            mars_rover_ui: MarsRoverWindow::default(),
+            epic_ui: EpicWindow::default(),
+            eonet_ui: EonetWindow::default(),
+            sentry_ui: SentryWindow::default(),
+            fireball_ui: FireballWindow::default(),
+            exoplanet_ui: ExoplanetWindow::default(),
+            landsat_ui: LandsatWindow::default(),
+            techtransfer_ui: TechTransferWindow::default(),
+            tle_ui: TleWindow::default(),
             about: AboutWindow::default(),
+            welcome: WelcomeWindow::default(),
             api: ApiKeyWindow::default(),
+            profiles_ui: ProfilesWindow::default(),
+            network_ui: NetworkWindow::default(),
+            inspector_ui: NetworkInspectorWindow::default(),
+            errors: ErrorPanel::default(),
             parser: Parser::default(),
         }
     }
 }
 
 impl SpacePixUi {
-    pub fn new(cc: &eframe::CreationContext<'_>, parser: Parser) -> Self {
+    // key_error is why the profile's key couldn't be loaded, which starts the first run wizard
+    // or asks for the passphrase, proxy_override holds the proxy flags from the command line
+    pub fn new(
+        cc: &eframe::CreationContext<'_>,
+        parser: Parser,
+        key_error: Option<ApiKeyError>,
+        proxy_override: ProxySettings,
+    ) -> Self {
         egui_extras::install_image_loaders(&cc.egui_ctx);
         // This is also where you can customize the look and feel of egui using
         // `cc.egui_ctx.set_visuals` and `cc.egui_ctx.set_fonts`.
 
         // Load previous app state (if any).
         // Note that you must enable the `persistence` feature for this to work.
-        if let Some(storage) = cc.storage {
-            return eframe::get_value(storage, eframe::APP_KEY).unwrap_or_default();
-        }
+        let mut app: Self = match cc.storage {
+            Some(storage) => eframe::get_value(storage, eframe::APP_KEY).unwrap_or_default(),
+            None => Self::default(),
+        };
 
         // cc.egui_ctx.set_visuals();
 
-        Self {
-            parser,
-            ..Default::default()
+        // Timeouts, retries and proxies are saved with the app state, the client is shared by every request
+        app.network_ui.proxy_override = proxy_override;
+        if let Err(e) = configure_http_client(&app.network_ui.effective_settings()) {
+            tracing::warn!("Keeping the default network settings: {}", e);
+            app.network_ui.status_label = e.to_string();
+            app.network_ui.network_window_visible = true;
+        }
+
+        // The key store is the source of truth, the key is never saved with the app state
+        app.parser = parser;
+        if let Some(ApiKeyError::PassphraseRequired()) = key_error {
+            app.api.locked = true;
+            app.api.api_key_window_visible = true;
+        } else if let Some(e) = key_error {
+            app.welcome = WelcomeWindow {
+                key_error: match e {
+                    ApiKeyError::KeyFile(e) if e.kind() == std::io::ErrorKind::NotFound => String::default(),
+                    e => e.to_string(),
+                },
+                welcome_window_visible: true,
+                ..Default::default()
+            };
         }
+        app
     }
 
     #[allow(dead_code)]
@@ -78,7 +165,7 @@ impl SpacePixUi {
             egui::ViewportId::from_hash_of("about_viewport"),
             egui::ViewportBuilder::default()
                 .with_title("About Spacepix")
-                .with_inner_size([300.0, 200.0]),
+                .with_inner_size([340.0, 300.0]),
             |ctx, class| {
                 assert!(
                     class == egui::ViewportClass::Immediate,
@@ -129,7 +216,7 @@ impl SpacePixUi {
         egui::ViewportId::from_hash_of("invalid_neows_input_viewport"),
                                 egui::ViewportBuilder::default()
                                 .with_title("Invalid Input")
-                                .with_inner_size([300.0, 200.0]),
+                                .with_inner_size([340.0, 300.0]),
                                 |ctx, class| {
                                     assert!(
                                         class == egui::ViewportClass::Immediate,
@@ -158,12 +245,34 @@ impl SpacePixUi {
         self.apod_ui.apod_full_window_visible = state;
     }
 
+    // A profile locked with a passphrase or without a saved key opens the API key window
+    fn switch_profile(&mut self, name: &str) {
+        match self.parser.switch_profile(name) {
+            Ok(_) => self.api.key_set_label = String::default(),
+            Err(ApiKeyError::PassphraseRequired()) => {
+                self.api.locked = true;
+                self.api.api_key_window_visible = true;
+            }
+            Err(ApiKeyError::KeyFile(e)) if e.kind() == std::io::ErrorKind::NotFound => {
+                self.api.key_set_label = format!("No key saved for {} yet, using {}.", name, Parser::DEMO_KEY);
+                self.api.api_key_window_visible = true;
+            }
+            Err(e) => {
+                self.api.key_set_label = e.to_string();
+                self.api.api_key_window_visible = true;
+            }
+        }
+        if self.api.api_key_window_visible {
+            self.api.passphrase = String::default();
+        }
+    }
+
     fn show_api_input(&mut self, ctx: &egui::Context) {
         ctx.show_viewport_immediate(
             egui::ViewportId::from_hash_of("show_api_input_viewport"),
             egui::ViewportBuilder::default()
                 .with_title("API Key")
-                .with_inner_size([300.0, 200.0]),
+                .with_inner_size([340.0, 300.0]),
             |ctx, class| {
                 assert!(
                     class == egui::ViewportClass::Immediate,
@@ -174,22 +283,50 @@ impl SpacePixUi {
                     ui.with_layout(
                         egui::Layout::top_down_justified(egui::Align::Center),
                         |ui| {
+                            if self.api.locked {
+                                ui.heading("Unlock your saved NASA API Key.");
+                                ui.label("It's protected by a passphrase, Spacepix uses DEMO_KEY until it's unlocked.");
+                                ui.add(egui::TextEdit::singleline(&mut self.api.passphrase).password(true));
+                                if ui.button("Unlock").clicked() {
+                                    self.api.key_set_label = match self.parser.unlock(&self.api.passphrase) {
+                                        Ok(_) => {
+                                            self.api.locked = false;
+                                            String::from("API key unlocked and in use!")
+                                        }
+                                        Err(e) => e.to_string(),
+                                    };
+                                }
+                                ui.separator();
+                            }
                             ui.heading("Enter your NASA API Key below.");
+                            ui.label(format!("Saved to the {} profile.", self.parser.profile()));
                             ui.label("NOTE: Type DEMO_KEY to use the demo key, with limitations.");
                             ui.text_edit_singleline(&mut self.api.key);
+                            ui.label("Passphrase (optional, without one the key only opens on this computer):");
+                            ui.add(egui::TextEdit::singleline(&mut self.api.passphrase).password(true));
                             if ui.button("Submit").clicked() {
-                                match self.parser.set_api_key(
-                                    &path::Path::new("secret.json"),
-                                    self.api.key.clone(),
-                                ) {
+                                self.parser.set_passphrase(Some(self.api.passphrase.clone()));
+                                self.api.key_set_label = match self.parser.change_api_key_blocking(&self.api.key) {
                                     Ok(_) => {
-                                        ui.label("Api key Set!");
+                                        self.api.locked = false;
+                                        String::from("API key verified, encrypted, saved and in use!")
                                     }
-                                    Err(_) => {
-                                        ui.label(self.api.key_set_label.clone());
+                                    Err(e) => e.to_string(),
+                                };
+                            }
+                            if ui.button("Forget Key").clicked() {
+                                self.api.key_set_label = match self.parser.forget_api_key() {
+                                    Ok(_) => {
+                                        self.api.key = String::default();
+                                        self.api.passphrase = String::default();
+                                        self.api.locked = false;
+                                        self.parser.set_passphrase(None);
+                                        String::from("Saved API key deleted, using DEMO_KEY.")
                                     }
-                                }
+                                    Err(e) => e.to_string(),
+                                };
                             }
+                            ui.label(&self.api.key_set_label);
 
                             if ui.link("Don't have a NASA API Key?").clicked() {
                                 match open::that("https://api.nasa.gov/") {
@@ -218,7 +355,10 @@ impl eframe::App for SpacePixUi {
             egui::menu::bar(ui, |ui| {
                 ui.menu_button("File", |ui| {
                     if ui.button("Save").clicked() {
-                        println!("Save");
+                        if let Some(apod) = &self.apod {
+                            self.apod_ui.save_apod(apod, &mut self.errors);
+                            self.apod_ui.apod_window_visible = true; // Show where it was saved
+                        }
                         ui.close_menu();
                     }
 
@@ -237,6 +377,56 @@ impl eframe::App for SpacePixUi {
                         ui.close_menu();
                     }
 
+                    if ui.button("DONKI - WSA-Enlil Simulations").clicked() {
+                        self.wsa_enlil_ui.wsa_enlil_window_visible = true;
+                        ui.close_menu();
+                    }
+
+                    if ui.button("Mars Rover Photos").clicked() {
+                        self.mars_rover_ui.mars_rover_window_visible = true;
+                        ui.close_menu();
+                    }
+
+                    if ui.button("EPIC - Earth Imagery").clicked() {
+                        self.epic_ui.epic_window_visible = true;
+                        ui.close_menu();
+                    }
+
+                    if ui.button("EONET - Natural Events").clicked() {
+                        self.eonet_ui.eonet_window_visible = true;
+                        ui.close_menu();
+                    }
+
+                    if ui.button("Sentry - Impact Risk").clicked() {
+                        self.sentry_ui.sentry_window_visible = true;
+                        ui.close_menu();
+                    }
+
+                    if ui.button("Fireballs - Bolide Reports").clicked() {
+                        self.fireball_ui.fireball_window_visible = true;
+                        ui.close_menu();
+                    }
+
+                    if ui.button("Exoplanet Archive").clicked() {
+                        self.exoplanet_ui.exoplanet_window_visible = true;
+                        ui.close_menu();
+                    }
+
+                    if ui.button("Landsat - Earth Imagery").clicked() {
+                        self.landsat_ui.landsat_window_visible = true;
+                        ui.close_menu();
+                    }
+
+                    if ui.button("TechTransfer - Patents and Software").clicked() {
+                        self.techtransfer_ui.techtransfer_window_visible = true;
+                        ui.close_menu();
+                    }
+
+                    if ui.button("Satellites - TLE Ground Tracks").clicked() {
+                        self.tle_ui.tle_window_visible = true;
+                        ui.close_menu();
+                    }
+
                     ui.separator();
 
                     if ui.button("Quit").clicked() {
@@ -251,15 +441,36 @@ impl eframe::App for SpacePixUi {
                         ui.close_menu();
                     }
 
+                    ui.menu_button("Key Profile", |ui| {
+                        let names: Vec<String> = self.parser.profiles().names().map(String::from).collect();
+                        for name in names {
+                            let in_use = !self.parser.is_key_overridden() && name == self.parser.profile();
+                            if ui.radio(in_use, &name).clicked() {
+                                self.switch_profile(&name);
+                                ui.close_menu();
+                            }
+                        }
+                        ui.separator();
+                        if ui.button("Manage Profiles").clicked() {
+                            self.profiles_ui.profiles_window_visible = true;
+                            ui.close_menu();
+                        }
+                    });
+
+                    if ui.button("Network").clicked() {
+                        self.network_ui.network_window_visible = true;
+                        ui.close_menu();
+                    }
+
                     ui.menu_button("Theme", |ui| {
                         if ui.button("Dark").clicked() {
-                            println!("Set Dark Theme");
+                            tracing::debug!("Switched to the dark theme");
                             // egui::style::Visuals::dark();
                             ui.visuals_mut().dark_mode = true;
                         }
 
                         if ui.button("Light").clicked() {
-                            println!("Set Light Theme");
+                            tracing::debug!("Switched to the light theme");
                             // egui::style::Visuals::light();
                             ui.visuals_mut().dark_mode = false;
                         }
@@ -271,10 +482,31 @@ impl eframe::App for SpacePixUi {
                         self.about.about_window_visible = true; // Set about_window_visible to true so on next update() it will come up.
                         ui.close_menu();
                     }
+
+                    if ui.button("Recent Errors").clicked() {
+                        self.errors.history_window_visible = true;
+                        ui.close_menu();
+                    }
+
+                    if ui.button("Network Inspector").clicked() {
+                        self.inspector_ui.inspector_window_visible = true;
+                        ui.close_menu();
+                    }
+
+                    // The logs to attach to a bug report
+                    if ui.button("Open Log Folder").clicked() {
+                        let log_dir = ConfigDirs::default().log_dir();
+                        if let Err(e) = open::that(&log_dir) {
+                            tracing::warn!("Failed to open {}: {}", log_dir.display(), e);
+                        }
+                        ui.close_menu();
+                    }
                 });
             });
         });
 
+        self.errors.error_panel(ctx); // Before the central panel so it gets its strip at the bottom
+
         egui::CentralPanel::default().show(ctx, |ui| {
             let mut apod_window_visible = self.apod_ui.apod_window_visible; // Set a local variable to I don't have to use self with .open() below
                                                                             // APOD //
@@ -303,6 +535,12 @@ impl eframe::App for SpacePixUi {
                                     "Copyright: {}",
                                     data.copyright.clone().replace("\n", "")
                                 ));
+                                ui.horizontal(|ui| {
+                                    if ui.button("Save Image").clicked() {
+                                        self.apod_ui.save_apod(data, &mut self.errors);
+                                    }
+                                    ui.label(&self.apod_ui.save_label);
+                                });
                                 ui.heading(
                                     RichText::new("Description:").font(FontId::monospace(30.0)),
                                 );
@@ -323,56 +561,89 @@ impl eframe::App for SpacePixUi {
                                     );
                                 }
                             }
-                            None => match Apod::get_apod_data_blocking() {
-                                Ok(apod) => self.apod = Some(apod),
-                                Err(e) => {
-                                    ui.label("Network Error");
+                            // Fetch once when opened, after a failure only the Retry button tries again
+                            None => {
+                                if !self.apod_ui.fetch_failed || self.errors.take_retry(ErrorSource::Apod) {
+                                    match Apod::get_apod_data_blocking(&self.parser) {
+                                        Ok(apod) => {
+                                            self.apod = Some(apod);
+                                            self.apod_ui.fetch_failed = false;
+                                        }
+                                        Err(e) => {
+                                            self.apod_ui.error_label = self.errors.report(ErrorSource::Apod, &e);
+                                            self.apod_ui.fetch_failed = true;
+                                        }
+                                    }
                                 }
-                            },
+                                ui.label(&self.apod_ui.error_label);
+                            }
                         }
                     });
                 }); // APOD //
             self.apod_ui.apod_window_visible = apod_window_visible;
 
+            let mut neows_window_visible = self.neows_ui.neows_window_visible;
             egui::Window::new("Asteroids - NeoWs")
-                .open(&mut self.neows_ui.neows_window_visible)
+                .open(&mut neows_window_visible)
                 .show(ctx, |ui| {
                     // NEOWS //
                     let mut next_search: Option<NEOFeed> = None;
                     egui::Frame::default().show(ui, |ui| {
-                        ui.label("Enter in a date to start your search from.");
-                        ui.label("Date format: YYYY-MM-DD");
-
-                        ui.label("Start Date:");
-                        ui.text_edit_singleline(&mut self.neows_ui.neows_date);
-                        egui::Grid::new("button_grid")
-                            .num_columns(3)
-                            .spacing([20.0, 20.0])
-                            .show(ui, |ui| {
-                                if ui.button("Previous").clicked() {
-                                    println!("Clicked Previous");
-                                    // Set previous cache to current cache
-                                } else if ui.button("Search").clicked() {
-                                    println!("Clicked Search");
-                                    let mut neows = NEOFeed::default();
-                                    match neows.get_neows_feed_blocking(&self.neows_ui.neows_date) {
-                                        Ok(_) => {
-                                            // next_search = Some(neows);
-                                            self.neows = Some(neows);
-                                            dbg!(&next_search);
-                                        }
-                                        Err(e) => {
-                                            ui.label(e.to_string());
-                                            next_search = None
-                                        }
+                        ui.horizontal(|ui| {
+                            ui.radio_value(&mut self.neows_ui.source, NeoSource::NeoWs, "NeoWs (1 day)");
+                            ui.radio_value(&mut self.neows_ui.source, NeoSource::Cad, "JPL CAD (any range)");
+                        });
+                        if self.neows_ui.source == NeoSource::Cad {
+                            if self.neows_ui.cad_search(ui) || self.errors.take_retry(ErrorSource::Cad) {
+                                let mut neows = NEOFeed::default();
+                                match neows.get_cad_feed_blocking(&self.parser, &self.neows_ui.cad) {
+                                    Ok(_) => {
+                                        self.neows = Some(neows);
+                                        self.neows_ui.error_label = String::default();
                                     }
-
-                                    // Load new search date
-                                } else if ui.button("Next").clicked() {
-                                    println!("Clicked Next");
-                                    // Load the next url cache from the searched date
+                                    Err(e) => self.neows_ui.error_label = self.errors.report(ErrorSource::Cad, &e),
                                 }
-                            });
+                            }
+                        } else {
+                            ui.label("Enter in a date to start your search from.");
+                            ui.label("Date format: YYYY-MM-DD");
+
+                            ui.label("Start Date:");
+                            ui.text_edit_singleline(&mut self.neows_ui.neows_date);
+                            egui::Grid::new("button_grid")
+                                .num_columns(3)
+                                .spacing([20.0, 20.0])
+                                .show(ui, |ui| {
+                                    if ui.button("Previous").clicked() {
+                                        tracing::debug!("NeoWs Previous clicked");
+                                        // Set previous cache to current cache
+                                    } else if ui.button("Search").clicked() || self.errors.take_retry(ErrorSource::NeoWs) {
+                                        tracing::info!(date = %self.neows_ui.neows_date, "Searching NeoWs");
+                                        let mut neows = NEOFeed::default();
+                                        match neows.get_neows_feed_blocking(&self.parser, &self.neows_ui.neows_date) {
+                                            Ok(_) => {
+                                                // next_search = Some(neows);
+                                                tracing::debug!(close_approaches = neows.element_count, "Loaded the NeoWs feed");
+                                                self.neows = Some(neows);
+                                                self.neows_ui.error_label = String::default();
+                                            }
+                                            Err(e) => {
+                                                self.neows_ui.error_label = self.errors.report(ErrorSource::NeoWs, &e);
+                                                next_search = None
+                                            }
+                                        }
+
+                                        // Load new search date
+                                    } else if ui.button("Next").clicked() {
+                                        tracing::debug!("NeoWs Next clicked");
+                                        // Load the next url cache from the searched date
+                                    }
+                                });
+                        }
+                        ui.label(&self.neows_ui.error_label);
+                        if let Some(neo) = &self.neows {
+                            ui.label(format!("{} close approaches", neo.element_count));
+                        }
                         match &self.neows {
                             Some(neo) => {
                                 // Display any NeoWs
@@ -443,10 +714,21 @@ impl eframe::App for SpacePixUi {
                                             object.estimated_diameter.0 .0,
                                             object.estimated_diameter.0 .1
                                         ));
-                                        ui.label(format!(
-                                            "Deemed hazardous by NASA: {}",
-                                            object.is_potentially_hazardous_asteroid
-                                        ));
+                                        ui.label(match object.is_potentially_hazardous_asteroid {
+                                            Some(hazardous) => format!("Deemed hazardous by NASA: {}", hazardous),
+                                            None => String::from("Deemed hazardous by NASA: not classified by CAD"),
+                                        });
+                                        if object.is_sentry_object
+                                            && ui.button("Sentry Impact Risk").clicked()
+                                        {
+                                            // neo_reference_id is the SPK-ID Sentry looks objects up by
+                                            self.sentry_ui.open_record(
+                                                &mut self.sentry,
+                                                &self.parser,
+                                                &mut self.errors,
+                                                &SentryLookup::Spk(object.neo_reference_id.clone()),
+                                            );
+                                        }
                                         ui.separator();
                                     }
                                 }); // Scroll Area
@@ -458,15 +740,53 @@ impl eframe::App for SpacePixUi {
                         }
                     });
                 }); // NEOWS //
+            self.neows_ui.neows_window_visible = neows_window_visible;
 
-            egui::Window::new("NASA Image and Video Library")
-                .open(&mut self.nivl_ui.nivl_window_visible)
-                .show(ctx, |ui| {
-                    egui::Frame::default().show(ui, |ui| {
-                        ui.label("Enter a search term");
-                        ui.text_edit_singleline(&mut self.nivl_ui.query);
-                    })
-                }); // NIVL
+            self.nivl_ui.nivl_window(&mut self.nivl, &self.parser, &mut self.errors, ctx); // NIVL
+
+            self.wsa_enlil_ui
+                .wsa_enlil_window(&mut self.wsa_enlil, &self.parser, &mut self.errors, ctx); // WSA-Enlil
+
+            self.mars_rover_ui.mars_rover_window(
+                &mut self.mars_photos,
+                &mut self.mars_manifest,
+                &self.parser,
+                &mut self.errors,
+                ctx,
+            ); // Mars Rover Photos
+
+            self.epic_ui.epic_window(&mut self.epic, &self.parser, &mut self.errors, ctx); // EPIC
+
+            self.eonet_ui.eonet_window(&mut self.eonet, &self.parser, &mut self.errors, ctx); // EONET
+
+            self.sentry_ui.sentry_window(&mut self.sentry, &self.parser, &mut self.errors, ctx); // Sentry
+
+            self.fireball_ui
+                .fireball_window(&mut self.fireballs, &self.parser, &mut self.errors, ctx); // Fireballs
+
+            self.exoplanet_ui
+                .exoplanet_window(&mut self.exoplanets, &self.parser, &mut self.errors, ctx); // Exoplanets
+
+            self.landsat_ui
+                .landsat_window(&mut self.landsat, &self.parser, &mut self.errors, ctx); // Landsat
+
+            self.techtransfer_ui
+                .techtransfer_window(&mut self.techtransfer, &self.parser, &mut self.errors, ctx); // TechTransfer
+
+            self.tle_ui.tle_window(&mut self.tle_search, &self.parser, &mut self.errors, ctx); // TLE
+
+            if self.welcome.welcome_window_visible {
+                self.welcome
+                    .welcome_window(&mut self.parser, ctx); // First run
+            }
+
+            if let Some(profile) = self.profiles_ui.profiles_window(&mut self.parser, ctx) {
+                self.switch_profile(&profile); // Key Profiles
+            }
+
+            self.network_ui.network_window(ctx); // Network
+
+            self.inspector_ui.inspector_window(ctx); // Network Inspector
         });
         if self.api.api_key_window_visible {
             self.show_api_input(&ctx.clone());
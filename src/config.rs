@@ -0,0 +1,146 @@
+use crate::Parser;
+use std::ffi::OsString;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Where Spacepix keeps its files, resolved the way each platform expects
+///
+/// * Linux and the BSDs: `$XDG_CONFIG_HOME/spacepix` and `$XDG_DATA_HOME/spacepix`,
+///   defaulting to `~/.config/spacepix` and `~/.local/share/spacepix`
+/// * macOS: `~/Library/Application Support/Spacepix` for both
+/// * Windows: `%APPDATA%\Spacepix` and `%LOCALAPPDATA%\Spacepix`
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigDirs {
+    pub config: PathBuf,
+    pub data: PathBuf,
+}
+
+impl Default for ConfigDirs {
+    fn default() -> Self {
+        Self::from_env(|name| std::env::var_os(name))
+    }
+}
+
+impl ConfigDirs {
+    pub const KEY_FILE: &'static str = "secret.json";
+    pub const ENCRYPTED_KEY_FILE: &'static str = "key.enc.json";
+
+    fn from_env(var: impl Fn(&str) -> Option<OsString>) -> Self {
+        // Relative XDG paths are invalid per the spec and get ignored
+        let absolute = |name: &str| var(name).map(PathBuf::from).filter(|path| path.is_absolute());
+        let home = var("HOME").map(PathBuf::from);
+
+        if cfg!(windows) {
+            let roaming = absolute("APPDATA").unwrap_or_else(|| PathBuf::from("."));
+            let local = absolute("LOCALAPPDATA").unwrap_or_else(|| roaming.clone());
+            Self {
+                config: roaming.join("Spacepix"),
+                data: local.join("Spacepix"),
+            }
+        } else if cfg!(target_os = "macos") {
+            let support = home.map_or(PathBuf::from("."), |home| home.join("Library").join("Application Support"));
+            Self {
+                config: support.join("Spacepix"),
+                data: support.join("Spacepix"),
+            }
+        } else {
+            let config = absolute("XDG_CONFIG_HOME").or_else(|| home.as_ref().map(|home| home.join(".config")));
+            let data = absolute("XDG_DATA_HOME").or_else(|| home.as_ref().map(|home| home.join(".local").join("share")));
+            Self {
+                config: config.unwrap_or_else(|| PathBuf::from(".")).join("spacepix"),
+                data: data.unwrap_or_else(|| PathBuf::from(".")).join("spacepix"),
+            }
+        }
+    }
+
+    pub fn key_file(&self) -> PathBuf {
+        self.config.join(Self::KEY_FILE)
+    }
+
+    pub fn encrypted_key_file(&self) -> PathBuf {
+        self.config.join(Self::ENCRYPTED_KEY_FILE)
+    }
+
+    pub fn log_dir(&self) -> PathBuf {
+        self.data.join("logs")
+    }
+
+    // Move a key file left in the working directory by older versions into the config directory,
+    // returns whether anything was moved. Only done before any key was saved in the config
+    // directory, and only for a file that holds a key, any other secret.json isn't ours to take
+    pub fn migrate_key_file(&self, legacy: &Path) -> io::Result<bool> {
+        let key_file = self.key_file();
+        if key_file.exists() || self.encrypted_key_file().exists() || !legacy.is_file() {
+            return Ok(false);
+        }
+        if Parser::read_key_file(fs::File::open(legacy)?).is_err() {
+            return Ok(false);
+        }
+        fs::create_dir_all(&self.config)?;
+        // Copy rather than rename, the working directory may be on another file system
+        fs::copy(legacy, &key_file)?;
+        fs::remove_file(legacy)?;
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ConfigDirs;
+    use std::ffi::OsString;
+    use std::fs;
+    use std::path::PathBuf;
+
+    #[test]
+    #[cfg(all(unix, not(target_os = "macos")))]
+    fn test_xdg_dirs() {
+        let dirs = ConfigDirs::from_env(|name| match name {
+            "HOME" => Some(OsString::from("/home/astronaut")),
+            "XDG_DATA_HOME" => Some(OsString::from("/srv/data")),
+            "XDG_CONFIG_HOME" => Some(OsString::from("relative/config")),
+            _ => None,
+        });
+        assert_eq!(dirs.config, PathBuf::from("/home/astronaut/.config/spacepix"));
+        assert_eq!(dirs.data, PathBuf::from("/srv/data/spacepix"));
+        assert_eq!(dirs.key_file(), PathBuf::from("/home/astronaut/.config/spacepix/secret.json"));
+        assert_eq!(dirs.encrypted_key_file(), PathBuf::from("/home/astronaut/.config/spacepix/key.enc.json"));
+        assert_eq!(dirs.log_dir(), PathBuf::from("/srv/data/spacepix/logs"));
+    }
+
+    #[test]
+    fn test_migrate_key_file() {
+        let root = std::env::temp_dir().join(format!("spacepix-config-test-{}", std::process::id()));
+        let dirs = ConfigDirs {
+            config: root.join("config"),
+            data: root.join("data"),
+        };
+        let legacy = root.join("secret.json");
+        fs::create_dir_all(&root).unwrap();
+        fs::write(&legacy, r#"{"key":"DEMO_KEY"}"#).unwrap();
+
+        assert!(dirs.migrate_key_file(&legacy).unwrap());
+        assert!(!legacy.exists());
+        assert_eq!(fs::read_to_string(dirs.key_file()).unwrap(), r#"{"key":"DEMO_KEY"}"#);
+
+        // An existing key file is never overwritten
+        fs::write(&legacy, r#"{"key":"OLD_KEY"}"#).unwrap();
+        assert!(!dirs.migrate_key_file(&legacy).unwrap());
+        assert_eq!(fs::read_to_string(dirs.key_file()).unwrap(), r#"{"key":"DEMO_KEY"}"#);
+
+        // Once the key is encrypted the plain copy is gone, the legacy file still stays put
+        fs::remove_file(dirs.key_file()).unwrap();
+        fs::write(dirs.encrypted_key_file(), "{}").unwrap();
+        assert!(!dirs.migrate_key_file(&legacy).unwrap());
+        assert!(legacy.exists() && !dirs.key_file().exists());
+
+        // Some other program's secret.json isn't a key file
+        fs::remove_file(dirs.encrypted_key_file()).unwrap();
+        fs::write(&legacy, r#"{"token":"not ours"}"#).unwrap();
+        assert!(!dirs.migrate_key_file(&legacy).unwrap());
+        assert_eq!(fs::read_to_string(&legacy).unwrap(), r#"{"token":"not ours"}"#);
+        assert!(!dirs.key_file().exists());
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
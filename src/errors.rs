@@ -1,23 +1,177 @@
 use thiserror::Error;
 
+/// Everything that can go wrong fetching from an API, the underlying error is kept as the source
 #[derive(Error, Debug)]
-pub enum NetworkError {
-    #[error("Failed to connect to NASA API")]
-    ConnectionFailed(#[from] reqwest::Error),
-    #[error("Failed to parse APOD image JSON")]
-    JsonParseFailed(#[from] json::Error)
+pub enum SpacepixError {
+    #[error("The server answered with HTTP {status}")]
+    Status {
+        status: u16,
+        #[source]
+        source: reqwest::Error,
+    },
+    #[error("The request timed out")]
+    Timeout(#[source] reqwest::Error),
+    #[error("Couldn't look up the server's address, check the internet connection")]
+    Dns(#[source] reqwest::Error),
+    #[error("Failed to reach the server")]
+    Connection(#[source] reqwest::Error),
+    #[error("Failed to set up the HTTP client")]
+    Client(#[source] reqwest::Error),
+    #[error("\"{url}\" isn't a usable proxy URL")]
+    Proxy {
+        url: String,
+        #[source]
+        source: reqwest::Error,
+    },
+    #[error("Failed to read the certificate file {}", .path.display())]
+    CertificateFile {
+        path: std::path::PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+    #[error("{} doesn't hold a usable PEM or DER certificate", .path.display())]
+    Certificate {
+        path: std::path::PathBuf,
+        #[source]
+        source: Option<reqwest::Error>,
+    },
+    #[error("{0}")]
+    NotFound(String), // The API answered, but doesn't have what was asked for
+    #[error("The response isn't valid JSON")]
+    Parse(#[from] json::Error),
+    #[error("Failed to save the downloaded file")]
+    Save(#[from] std::io::Error),
+    #[error(transparent)]
+    ApiKey(#[from] ApiKeyError),
+    #[error(transparent)]
+    Tle(#[from] TleError),
 }
 
-#[derive(Error, Debug)]
-pub enum NeowsError {
-    #[error("Bad Request")]
-    BadRequest(#[from] reqwest::Error)
+impl From<reqwest::Error> for SpacepixError {
+    fn from(e: reqwest::Error) -> Self {
+        match e.status() {
+            Some(status) => Self::Status {
+                status: status.as_u16(),
+                source: e,
+            },
+            None if e.is_timeout() => Self::Timeout(e),
+            None if is_dns_error(&e) => Self::Dns(e),
+            None => Self::Connection(e),
+        }
+    }
+}
+
+impl SpacepixError {
+    // This error's message followed by each of its sources, for the error panel's details
+    pub fn chain(&self) -> Vec<String> {
+        let mut chain = vec![self.to_string()];
+        let mut source = std::error::Error::source(self);
+        while let Some(e) = source {
+            chain.push(e.to_string());
+            source = e.source();
+        }
+        chain
+    }
+
+    // Whether trying again later could work, a bad request or key won't fix itself
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Self::Status { status, .. } => *status == 429 || *status >= 500,
+            Self::Timeout(_) | Self::Dns(_) | Self::Connection(_) => true,
+            _ => false,
+        }
+    }
+
+    // The server couldn't be reached at all, so the next request to it will most likely wait out
+    // its whole time budget too
+    pub fn is_unreachable(&self) -> bool {
+        matches!(self, Self::Timeout(_) | Self::Dns(_) | Self::Connection(_))
+    }
+}
+
+// reqwest doesn't have a kind for failed lookups, hyper reports them as a "dns error" source
+fn is_dns_error(e: &reqwest::Error) -> bool {
+    let mut source = std::error::Error::source(e);
+    while let Some(e) = source {
+        if e.to_string().contains("dns error") {
+            return true;
+        }
+        source = e.source();
+    }
+    false
 }
 
 #[derive(Error, Debug)]
 pub enum ApiKeyError {
-    #[error("Invalid API key")]
+    #[error("NASA doesn't recognize this API key")]
     InvalidApiKey(),
+    #[error("Couldn't check the API key with NASA: {0}")]
+    Unreachable(String),
     #[error("Spacepix either can't find or can't access the API key file")]
-    KeyFile(#[from] std::io::Error)
-}
\ No newline at end of file
+    KeyFile(#[from] std::io::Error),
+    #[error("The API key file isn't valid JSON")]
+    MalformedKeyFile(#[from] serde_json::Error),
+    #[error("The API key file doesn't contain a key")]
+    MissingKey(),
+    #[error("The saved API key is protected by a passphrase")]
+    PassphraseRequired(),
+    #[error("The saved API key can't be decrypted, check the passphrase")]
+    Decryption(),
+    #[error("Failed to encrypt the API key")]
+    Encryption(),
+    #[error("\"{0}\" isn't a usable profile name, use up to 32 letters, numbers, - and _")]
+    InvalidProfileName(String),
+    #[error("There is no key profile called \"{0}\"")]
+    UnknownProfile(String),
+}
+#[derive(Error, Debug)]
+pub enum TleError {
+    #[error("Failed to parse the two-line element set")]
+    Parse(#[from] sgp4::TleError),
+    #[error("The two-line element set can't be propagated")]
+    Elements(#[from] sgp4::ElementsError),
+    #[error("Failed to propagate the orbit")]
+    Propagation(#[from] sgp4::Error),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ApiKeyError, SpacepixError};
+    use crate::testing::{http_response, stub_server};
+    use std::net::TcpListener;
+
+    fn get(url: &str) -> SpacepixError {
+        reqwest::blocking::get(url)
+            .and_then(|response| response.error_for_status())
+            .unwrap_err()
+            .into()
+    }
+
+    #[test]
+    fn test_status_errors() {
+        let unavailable = get(&stub_server(vec![Some(http_response("503 Service Unavailable", ""))]).0);
+        assert!(matches!(unavailable, SpacepixError::Status { status: 503, .. }));
+        assert!(unavailable.is_retryable());
+        assert!(!unavailable.is_unreachable());
+        assert!(unavailable.chain().len() > 1);
+
+        let not_found = get(&stub_server(vec![Some(http_response("404 Not Found", ""))]).0);
+        assert!(matches!(not_found, SpacepixError::Status { status: 404, .. }));
+        assert!(!not_found.is_retryable());
+    }
+
+    #[test]
+    fn test_connection_errors() {
+        // Nothing listens on a port that was just given back
+        let url = format!("http://{}/", TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap());
+        let refused = get(&url);
+        assert!(matches!(refused, SpacepixError::Connection(_)));
+        assert!(refused.is_retryable());
+        assert!(refused.is_unreachable());
+
+        let parse: SpacepixError = json::parse("{").unwrap_err().into();
+        assert!(!parse.is_retryable());
+        let key: SpacepixError = ApiKeyError::InvalidApiKey().into();
+        assert_eq!(key.chain(), ["NASA doesn't recognize this API key"]);
+    }
+}
@@ -0,0 +1,592 @@
+use crate::errors::ApiKeyError;
+use crate::ConfigDirs;
+use argon2::Argon2;
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::{Aead, KeyInit, OsRng};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use serde_json::json;
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{self, Write};
+#[cfg(unix)]
+use std::os::unix::fs::{DirBuilderExt, OpenOptionsExt, PermissionsExt};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+/**
+ * The NASA API key, encrypted on disk with ChaCha20-Poly1305
+ * The encryption key is derived with Argon2 from either a passphrase the user picks or a random
+ * machine secret kept next to the key file, readable only by this user. The machine secret only
+ * keeps the key from other users on the machine, anyone who can run programs as this user or
+ * copies the whole config directory can still get it.
+ */
+#[derive(Debug, Clone)]
+pub struct KeyStore {
+    path: PathBuf,
+    secret_dir: PathBuf, // Where the machine secret is kept
+    machine_secret: OnceLock<Vec<u8>>, // Read or created the first time a key is saved or loaded
+    passphrase: Option<String>,
+}
+
+impl Default for KeyStore {
+    fn default() -> Self {
+        Self::new(ConfigDirs::default().encrypted_key_file())
+    }
+}
+
+impl KeyStore {
+    const VERSION: u32 = 1;
+    const SALT_LEN: usize = 16;
+    const NONCE_LEN: usize = 12;
+    const SECRET_LEN: usize = 32;
+    const SECRET_FILE: &'static str = "machine.secret";
+
+    pub fn new(path: PathBuf) -> Self {
+        let dir = path.parent().map(Path::to_path_buf).unwrap_or_default();
+        Self::with_secret_dir(path, dir)
+    }
+
+    // Nothing is read or written until the key is, so building a store is free
+    fn with_secret_dir(path: PathBuf, secret_dir: PathBuf) -> Self {
+        Self {
+            path,
+            secret_dir,
+            machine_secret: OnceLock::new(),
+            passphrase: None,
+        }
+    }
+
+    // Lets tests stand in for the machine, no keyring or machine id needed
+    pub fn with_machine_secret(path: PathBuf, machine_secret: &[u8]) -> Self {
+        Self {
+            machine_secret: OnceLock::from(machine_secret.to_vec()),
+            ..Self::new(path)
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn exists(&self) -> bool {
+        self.path.is_file()
+    }
+
+    // Used to unlock the stored key and to protect the next key saved, None uses the machine secret
+    pub fn set_passphrase(&mut self, passphrase: Option<String>) {
+        self.passphrase = passphrase.filter(|passphrase| !passphrase.is_empty());
+    }
+
+    pub fn has_passphrase(&self) -> bool {
+        self.passphrase.is_some()
+    }
+
+    // Whether the stored key was saved with a passphrase rather than the machine secret
+    pub fn is_passphrase_protected(&self) -> Result<bool, ApiKeyError> {
+        Ok(self.read()?["protection"] == "passphrase")
+    }
+
+    pub fn save(&self, key: &str) -> Result<(), ApiKeyError> {
+        let mut salt = [0u8; Self::SALT_LEN];
+        let mut nonce = [0u8; Self::NONCE_LEN];
+        OsRng.fill_bytes(&mut salt);
+        OsRng.fill_bytes(&mut nonce);
+        let ciphertext = self
+            .cipher(&salt)?
+            .encrypt(Nonce::from_slice(&nonce), key.as_bytes())
+            .map_err(|_| ApiKeyError::Encryption())?;
+
+        let file = json!({
+            "version": Self::VERSION,
+            "protection": if self.has_passphrase() { "passphrase" } else { "machine" },
+            "salt": to_hex(&salt),
+            "nonce": to_hex(&nonce),
+            "ciphertext": to_hex(&ciphertext),
+        });
+        if let Some(dir) = self.path.parent() {
+            create_private_dir(dir)?;
+        }
+        write_private(&self.path, serde_json::to_string_pretty(&file)?.as_bytes())?;
+        Ok(())
+    }
+
+    pub fn load(&self) -> Result<String, ApiKeyError> {
+        let file = self.read()?;
+        if file["protection"] == "passphrase" && !self.has_passphrase() {
+            return Err(ApiKeyError::PassphraseRequired());
+        }
+        let field = |name: &str| from_hex(file[name].as_str().unwrap_or_default()).ok_or(ApiKeyError::MissingKey());
+        let (salt, nonce, ciphertext) = (field("salt")?, field("nonce")?, field("ciphertext")?);
+        if nonce.len() != Self::NONCE_LEN {
+            return Err(ApiKeyError::MissingKey());
+        }
+        let decrypt = |secret: &[u8]| {
+            let key = Self::cipher_with(secret, &salt)?
+                .decrypt(Nonce::from_slice(&nonce), ciphertext.as_slice())
+                .map_err(|_| ApiKeyError::Decryption())?;
+            String::from_utf8(key).map_err(|_| ApiKeyError::Decryption())
+        };
+        match (decrypt(self.secret()), &self.passphrase) {
+            // Keys saved before the secret file existed used a secret built from the environment,
+            // move them over so they stop depending on how Spacepix was started
+            (Err(ApiKeyError::Decryption()), None) => {
+                let key = decrypt(&Self::legacy_machine_secret())?;
+                if let Err(e) = self.save(&key) {
+                    tracing::warn!("Failed to re-encrypt {}: {}", self.path.display(), e);
+                }
+                Ok(key)
+            }
+            (result, _) => result,
+        }
+    }
+
+    pub fn forget(&self) -> Result<(), ApiKeyError> {
+        match fs::remove_file(&self.path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(ApiKeyError::KeyFile(e)),
+            _ => Ok(()),
+        }
+    }
+
+    // Encrypt a plain text key file from older versions and delete it, returns whether anything was moved
+    pub fn migrate_plaintext(&self, plaintext: &Path) -> Result<bool, ApiKeyError> {
+        if !plaintext.is_file() {
+            return Ok(false);
+        }
+        // The encrypted key wins, but the plain copy mustn't stay on disk next to it
+        if self.exists() {
+            tracing::warn!(
+                "Deleting the plain text key in {}, a key is already encrypted in {}",
+                plaintext.display(),
+                self.path.display()
+            );
+            fs::remove_file(plaintext)?;
+            return Ok(false);
+        }
+        self.save(&crate::Parser::read_key_file(fs::File::open(plaintext)?)?)?;
+        fs::remove_file(plaintext)?;
+        Ok(true)
+    }
+
+    fn read(&self) -> Result<serde_json::Value, ApiKeyError> {
+        Ok(serde_json::from_str(&fs::read_to_string(&self.path)?)?)
+    }
+
+    fn secret(&self) -> &[u8] {
+        match &self.passphrase {
+            Some(passphrase) => passphrase.as_bytes(),
+            None => self.machine_secret.get_or_init(|| Self::machine_secret(&self.secret_dir)),
+        }
+    }
+
+    fn cipher(&self, salt: &[u8]) -> Result<ChaCha20Poly1305, ApiKeyError> {
+        Self::cipher_with(self.secret(), salt)
+    }
+
+    fn cipher_with(secret: &[u8], salt: &[u8]) -> Result<ChaCha20Poly1305, ApiKeyError> {
+        let mut key = [0u8; 32];
+        Argon2::default()
+            .hash_password_into(secret, salt, &mut key)
+            .map_err(|_| ApiKeyError::Encryption())?;
+        Ok(ChaCha20Poly1305::new(Key::from_slice(&key)))
+    }
+
+    // Random bytes saved in dir the first time, so the secret is the same whether Spacepix was
+    // started from a terminal or a desktop launcher. Falls back to the old environment based
+    // secret if the file can't be written
+    fn machine_secret(dir: &Path) -> Vec<u8> {
+        let path = dir.join(Self::SECRET_FILE);
+        match fs::read(&path) {
+            Ok(secret) if secret.len() == Self::SECRET_LEN => return secret,
+            Ok(_) => tracing::warn!("Replacing the damaged machine secret in {}", path.display()),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+            Err(e) => {
+                tracing::warn!("Failed to read {}: {}", path.display(), e);
+                return Self::legacy_machine_secret();
+            }
+        }
+        let mut secret = vec![0u8; Self::SECRET_LEN];
+        OsRng.fill_bytes(&mut secret);
+        match create_private_dir(dir).and_then(|_| write_private(&path, &secret)) {
+            Ok(_) => secret,
+            Err(e) => {
+                tracing::warn!("Failed to save {}: {}", path.display(), e);
+                Self::legacy_machine_secret()
+            }
+        }
+    }
+
+    // What versions before the secret file used, the machine id with the user's environment
+    fn legacy_machine_secret() -> Vec<u8> {
+        let machine_id = ["/etc/machine-id", "/var/lib/dbus/machine-id"]
+            .iter()
+            .find_map(|path| fs::read_to_string(path).ok())
+            .unwrap_or_default();
+        let var = |names: &[&str]| {
+            names
+                .iter()
+                .find_map(|name| std::env::var(name).ok())
+                .unwrap_or_default()
+        };
+        format!(
+            "spacepix:{}:{}:{}:{}",
+            machine_id.trim(),
+            var(&["COMPUTERNAME", "HOSTNAME"]),
+            var(&["USER", "USERNAME"]),
+            var(&["HOME", "USERPROFILE"]),
+        )
+        .into_bytes()
+    }
+}
+
+/// Named API keys, each encrypted in its own KeyStore, and the one Spacepix starts with
+/// The Default profile keeps the key file from before there were profiles.
+#[derive(Debug, Clone)]
+pub struct KeyProfiles {
+    dir: PathBuf,
+    machine_secret: Option<Vec<u8>>, // None reads the one in dir once a key is used
+    stores: BTreeMap<String, KeyStore>,
+    default: String,
+}
+
+// What gets saved to profiles.json, the keys themselves stay in their key stores
+#[derive(serde::Serialize, serde::Deserialize)]
+struct ProfileIndex {
+    default: String,
+    profiles: Vec<String>,
+}
+
+impl Default for KeyProfiles {
+    fn default() -> Self {
+        Self::new(ConfigDirs::default().config)
+    }
+}
+
+impl KeyProfiles {
+    pub const DEFAULT_PROFILE: &'static str = "Default";
+    const INDEX_FILE: &'static str = "profiles.json";
+
+    // Just the Default profile, call load() for the saved ones
+    pub fn new(dir: PathBuf) -> Self {
+        Self::with_secret(dir, None)
+    }
+
+    pub fn with_machine_secret(dir: PathBuf, machine_secret: &[u8]) -> Self {
+        Self::with_secret(dir, Some(machine_secret.to_vec()))
+    }
+
+    fn with_secret(dir: PathBuf, machine_secret: Option<Vec<u8>>) -> Self {
+        let mut profiles = Self {
+            dir,
+            machine_secret,
+            stores: BTreeMap::default(),
+            default: String::from(Self::DEFAULT_PROFILE),
+        };
+        profiles.insert(Self::DEFAULT_PROFILE);
+        profiles
+    }
+
+    // Read the saved profile list, there is nothing to read until a second profile is added
+    pub fn load(&mut self) -> Result<(), ApiKeyError> {
+        let index: ProfileIndex = match fs::read_to_string(self.dir.join(Self::INDEX_FILE)) {
+            Ok(index) => serde_json::from_str(&index)?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+            Err(e) => return Err(ApiKeyError::KeyFile(e)),
+        };
+        for name in index.profiles.iter().filter(|name| Self::is_name_valid(name)) {
+            self.insert(name);
+        }
+        if self.stores.contains_key(&index.default) {
+            self.default = index.default;
+        }
+        Ok(())
+    }
+
+    // Profile names end up in file names, so keep them to letters, digits, - and _
+    pub fn is_name_valid(name: &str) -> bool {
+        (1..=32).contains(&name.len()) && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.stores.keys().map(String::as_str)
+    }
+
+    pub fn default_profile(&self) -> &str {
+        &self.default
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.stores.contains_key(name)
+    }
+
+    pub fn store(&self, name: &str) -> Option<&KeyStore> {
+        self.stores.get(name)
+    }
+
+    pub fn store_mut(&mut self, name: &str) -> Option<&mut KeyStore> {
+        self.stores.get_mut(name)
+    }
+
+    pub fn add(&mut self, name: &str) -> Result<(), ApiKeyError> {
+        // Names differing only in case would share a key file on Windows and macOS
+        if !Self::is_name_valid(name) || self.names().any(|existing| existing.eq_ignore_ascii_case(name)) {
+            return Err(ApiKeyError::InvalidProfileName(name.to_string()));
+        }
+        self.insert(name);
+        self.save()
+    }
+
+    // Forget the profile's key and drop it, the Default profile always stays but loses its key
+    pub fn remove(&mut self, name: &str) -> Result<(), ApiKeyError> {
+        self.stores
+            .get(name)
+            .ok_or_else(|| ApiKeyError::UnknownProfile(name.to_string()))?
+            .forget()?;
+        if name != Self::DEFAULT_PROFILE {
+            self.stores.remove(name);
+        }
+        if self.default == name {
+            self.default = String::from(Self::DEFAULT_PROFILE);
+        }
+        self.save()
+    }
+
+    pub fn set_default(&mut self, name: &str) -> Result<(), ApiKeyError> {
+        if !self.contains(name) {
+            return Err(ApiKeyError::UnknownProfile(name.to_string()));
+        }
+        self.default = name.to_string();
+        self.save()
+    }
+
+    fn insert(&mut self, name: &str) {
+        let path = match name == Self::DEFAULT_PROFILE {
+            true => self.dir.join(ConfigDirs::ENCRYPTED_KEY_FILE),
+            false => self.dir.join("keys").join(format!("{}.enc.json", name)),
+        };
+        self.stores
+            .entry(name.to_string())
+            .or_insert_with(|| match &self.machine_secret {
+                Some(machine_secret) => KeyStore::with_machine_secret(path, machine_secret),
+                // Every profile shares the secret next to profiles.json, even those under keys/
+                None => KeyStore::with_secret_dir(path, self.dir.clone()),
+            });
+    }
+
+    fn save(&self) -> Result<(), ApiKeyError> {
+        let index = ProfileIndex {
+            default: self.default.clone(),
+            profiles: self.names().map(String::from).collect(),
+        };
+        create_private_dir(&self.dir)?;
+        write_private(&self.dir.join(Self::INDEX_FILE), serde_json::to_string_pretty(&index)?.as_bytes())?;
+        Ok(())
+    }
+}
+
+// Key files are only for this user, on a shared machine anyone who can read one can try to
+// decrypt it. Files from older versions get their permissions tightened when rewritten
+fn write_private(path: &Path, contents: &[u8]) -> io::Result<()> {
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    options.mode(0o600);
+    let mut file = options.open(path)?;
+    #[cfg(unix)]
+    file.set_permissions(fs::Permissions::from_mode(0o600))?;
+    file.write_all(contents)
+}
+
+fn create_private_dir(dir: &Path) -> io::Result<()> {
+    let mut builder = fs::DirBuilder::new();
+    builder.recursive(true);
+    #[cfg(unix)]
+    builder.mode(0o700);
+    builder.create(dir)?;
+    #[cfg(unix)]
+    fs::set_permissions(dir, fs::Permissions::from_mode(0o700))?;
+    Ok(())
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+fn from_hex(hex: &str) -> Option<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{from_hex, to_hex, KeyProfiles, KeyStore};
+    use crate::errors::ApiKeyError;
+    use std::fs;
+    use std::path::Path;
+
+    const KEY: &str = "abcdefghijABCDEFGHIJ0123456789abcdefghij";
+
+    fn temp_dir(name: &str) -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!("spacepix-keystore-{}-{}", name, std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_hex() {
+        assert_eq!(to_hex(&[0, 15, 255]), "000fff");
+        assert_eq!(from_hex("000fff"), Some(vec![0, 15, 255]));
+        assert_eq!(from_hex("0ff"), None);
+        assert_eq!(from_hex("zz"), None);
+    }
+
+    #[test]
+    fn test_machine_secret() {
+        let dir = temp_dir("machine");
+        let store = KeyStore::with_machine_secret(dir.join("key.json"), b"this machine");
+        store.save(KEY).unwrap();
+
+        assert!(!fs::read_to_string(store.path()).unwrap().contains(KEY));
+        assert!(!store.is_passphrase_protected().unwrap());
+        assert_eq!(store.load().unwrap(), KEY);
+
+        // A copy of the file is useless anywhere else
+        let elsewhere = KeyStore::with_machine_secret(dir.join("key.json"), b"another machine");
+        assert!(matches!(elsewhere.load(), Err(ApiKeyError::Decryption())));
+
+        store.forget().unwrap();
+        assert!(!store.exists());
+        assert!(store.forget().is_ok());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_machine_secret_file() {
+        let dir = temp_dir("secret");
+        let secret = KeyStore::machine_secret(&dir);
+        assert_eq!(secret.len(), KeyStore::SECRET_LEN);
+        assert_eq!(KeyStore::machine_secret(&dir), secret);
+        assert_private(&dir.join("machine.secret"), 0o600);
+
+        // Profiles and stores only touch the disk once a key is saved or loaded
+        let lazy = dir.join("lazy");
+        let profiles = KeyProfiles::new(lazy.clone());
+        assert!(!lazy.exists());
+        profiles.store("Default").unwrap().save(KEY).unwrap();
+        assert_eq!(KeyStore::machine_secret(&lazy).len(), KeyStore::SECRET_LEN);
+        assert_eq!(KeyStore::new(lazy.join("key.enc.json")).load().unwrap(), KEY);
+
+        // Keys from before the secret file are moved over to it the first time they're loaded
+        let path = dir.join("key.json");
+        KeyStore::with_machine_secret(path.clone(), &KeyStore::legacy_machine_secret()).save(KEY).unwrap();
+        let saved = fs::read_to_string(&path).unwrap();
+        let store = KeyStore::new(path.clone());
+        assert_eq!(store.load().unwrap(), KEY);
+        assert_ne!(fs::read_to_string(&path).unwrap(), saved);
+        let moved = KeyStore::with_machine_secret(path, &secret);
+        assert_eq!(moved.load().unwrap(), KEY);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_passphrase() {
+        let dir = temp_dir("passphrase");
+        let mut store = KeyStore::with_machine_secret(dir.join("key.json"), b"this machine");
+        store.set_passphrase(Some(String::from("correct horse")));
+        store.save(KEY).unwrap();
+        assert!(store.is_passphrase_protected().unwrap());
+
+        let mut locked = KeyStore::with_machine_secret(dir.join("key.json"), b"this machine");
+        assert!(matches!(locked.load(), Err(ApiKeyError::PassphraseRequired())));
+        locked.set_passphrase(Some(String::from("battery staple")));
+        assert!(matches!(locked.load(), Err(ApiKeyError::Decryption())));
+        locked.set_passphrase(Some(String::from("correct horse")));
+        assert_eq!(locked.load().unwrap(), KEY);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_migrate_plaintext() {
+        let dir = temp_dir("migrate");
+        let plaintext = dir.join("secret.json");
+        fs::write(&plaintext, format!(r#"{{"key":"{}"}}"#, KEY)).unwrap();
+        let store = KeyStore::with_machine_secret(dir.join("key.json"), b"this machine");
+
+        assert!(store.migrate_plaintext(&plaintext).unwrap());
+        assert!(!plaintext.exists());
+        assert_eq!(store.load().unwrap(), KEY);
+        assert!(!store.migrate_plaintext(&plaintext).unwrap());
+
+        // A plain copy turning up after the key was encrypted is deleted, not encrypted over it
+        fs::write(&plaintext, r#"{"key":"DEMO_KEY"}"#).unwrap();
+        assert!(!store.migrate_plaintext(&plaintext).unwrap());
+        assert!(!plaintext.exists());
+        assert_eq!(store.load().unwrap(), KEY);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    // Other users on the machine mustn't be able to read the key files
+    fn assert_private(path: &Path, mode: u32) {
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            assert_eq!(fs::metadata(path).unwrap().permissions().mode() & 0o777, mode);
+        }
+        #[cfg(not(unix))]
+        let _ = (path, mode);
+    }
+
+    #[test]
+    fn test_private_files() {
+        let dir = temp_dir("private");
+        let path = dir.join("key.json");
+        // A file left readable by an older version is tightened when the key is saved again
+        fs::write(&path, "{}").unwrap();
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&path, fs::Permissions::from_mode(0o644)).unwrap();
+        }
+        KeyStore::with_machine_secret(path.clone(), b"this machine").save(KEY).unwrap();
+        assert_private(&path, 0o600);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_key_profiles() {
+        let dir = temp_dir("profiles");
+        let mut profiles = KeyProfiles::with_machine_secret(dir.clone(), b"this machine");
+        assert_eq!(profiles.names().collect::<Vec<&str>>(), ["Default"]);
+
+        profiles.add("work").unwrap();
+        assert!(matches!(profiles.add("Work"), Err(ApiKeyError::InvalidProfileName(_))));
+        assert!(matches!(profiles.add("../up"), Err(ApiKeyError::InvalidProfileName(_))));
+        assert!(matches!(profiles.set_default("home"), Err(ApiKeyError::UnknownProfile(_))));
+        profiles.set_default("work").unwrap();
+        profiles.store("work").unwrap().save(KEY).unwrap();
+        profiles.store("Default").unwrap().save("DEMO_KEY").unwrap();
+        assert_eq!(profiles.store("Default").unwrap().path(), dir.join("key.enc.json"));
+        assert_private(&dir.join("profiles.json"), 0o600);
+        assert_private(&dir.join("keys"), 0o700);
+        assert_private(&dir.join("keys").join("work.enc.json"), 0o600);
+        assert_private(&dir.join("key.enc.json"), 0o600);
+
+        let mut loaded = KeyProfiles::with_machine_secret(dir.clone(), b"this machine");
+        loaded.load().unwrap();
+        assert_eq!(loaded.names().collect::<Vec<&str>>(), ["Default", "work"]);
+        assert_eq!(loaded.default_profile(), "work");
+        assert_eq!(loaded.store("work").unwrap().load().unwrap(), KEY);
+        assert_eq!(loaded.store("Default").unwrap().load().unwrap(), "DEMO_KEY");
+
+        loaded.remove("work").unwrap();
+        loaded.remove("Default").unwrap();
+        assert_eq!(loaded.names().collect::<Vec<&str>>(), ["Default"]);
+        assert_eq!(loaded.default_profile(), "Default");
+        assert!(!loaded.store("Default").unwrap().exists());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
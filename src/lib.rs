@@ -2,12 +2,27 @@
 mod apis;
 mod app;
 pub mod errors;
+mod config;
+mod keystore;
+mod logging;
 mod urls;
 mod parser;
+mod map;
+mod plot;
+mod orbit;
 mod ui;
+#[cfg(test)]
+mod testing;
 pub use apis::*;
 pub use app::SpacePixUi;
 pub use urls::Urls;
+pub use config::ConfigDirs;
+pub use keystore::{KeyProfiles, KeyStore};
+pub use logging::init_logging;
 pub use parser::Parser;
-pub use ui::{ApodWindow, NeowsWindow, NIVLWindow};
-pub use errors::{ApiKeyError, NetworkError};
+pub use ui::{
+    ApodWindow, EonetWindow, EpicWindow, ExoplanetWindow, FireballWindow, LandsatWindow,
+    MarsRoverWindow, NeoSource, NeowsWindow, NetworkInspectorWindow, NetworkWindow, NIVLWindow,
+    ProfilesWindow, SentryWindow, TechTransferWindow, TleWindow, WSAEnlilWindow, WelcomeWindow,
+};
+pub use errors::{ApiKeyError, SpacepixError, TleError};
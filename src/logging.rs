@@ -0,0 +1,59 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::field::MakeExt;
+use tracing_subscriber::fmt::{self, format, format::FmtSpan};
+use tracing_subscriber::prelude::*;
+use tracing_subscriber::EnvFilter;
+
+// Spacepix at info, dependencies only when something is wrong, RUST_LOG overrides both
+const DEFAULT_FILTER: &str = "warn,spacepix=info";
+// One file a day, the oldest is deleted once there are more
+pub const LOG_FILES: usize = 7;
+
+// Send diagnostics to the terminal and a daily log file in dir, log records from dependencies
+// included. The guard flushes the file when dropped, so keep it until the app exits
+pub fn init_logging(dir: &Path) -> Option<WorkerGuard> {
+    let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(DEFAULT_FILTER));
+    // The appender prunes old files before it creates the directory, so create it first
+    let appender = fs::create_dir_all(dir).map_err(|e| e.to_string()).and_then(|_| {
+        RollingFileAppender::builder()
+            .rotation(Rotation::DAILY)
+            .filename_prefix("spacepix")
+            .filename_suffix("log")
+            .max_log_files(LOG_FILES)
+            .build(dir)
+            .map_err(|e| e.to_string())
+    });
+    let (file, guard, file_error) = match appender {
+        Ok(appender) => {
+            let (writer, guard) = tracing_appender::non_blocking(appender);
+            // Closing a span logs how long the API call took. Span fields are formatted once per
+            // field formatter type, a formatter of its own keeps the terminal's colors out of the file
+            let layer = fmt::layer()
+                .with_ansi(false)
+                .fmt_fields(
+                    format::debug_fn(|writer, field, value| match field.name() {
+                        "message" => write!(writer, "{:?}", value),
+                        name => write!(writer, "{}={:?}", name, value),
+                    })
+                    .delimited(" "),
+                )
+                .with_span_events(FmtSpan::CLOSE)
+                .with_writer(writer);
+            (Some(layer), Some(guard), None)
+        }
+        Err(e) => (None, None, Some(e)),
+    };
+    tracing_subscriber::registry()
+        .with(filter)
+        .with(fmt::layer().with_writer(io::stderr))
+        .with(file)
+        .init();
+    if let Some(e) = file_error {
+        tracing::warn!("Logging to the terminal only, can't write logs to {}: {}", dir.display(), e);
+    }
+    guard
+}
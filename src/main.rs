@@ -1,14 +1,42 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")] // hide console window on Windows in release
 use egui;
-use spacepix::Parser;
-use std::{fs, io::Read};
+use clap::Parser as _;
+use spacepix::{ConfigDirs, KeyProfiles, KeyStore, Parser, ProxySettings};
+use std::path::{Path, PathBuf};
 
-const SECRET: &str = "secret.json";
+/// Explore NASA's open APIs
+#[derive(clap::Parser)]
+#[command(version)]
+struct Args {
+    /// NASA API key to use for this run instead of the key profile, also read from SPACEPIX_API_KEY
+    #[arg(long)]
+    key: Option<String>,
+    /// Proxy for http:// requests, e.g. http://proxy.lab:3128
+    #[arg(long, value_name = "URL")]
+    http_proxy: Option<String>,
+    /// Proxy for https:// requests
+    #[arg(long, value_name = "URL")]
+    https_proxy: Option<String>,
+    /// SOCKS5 proxy for anything the other proxies don't cover, e.g. socks5h://127.0.0.1:1080
+    #[arg(long, value_name = "URL")]
+    socks_proxy: Option<String>,
+    /// Comma separated hosts, domains and IP ranges that skip the proxies
+    #[arg(long, value_name = "HOSTS")]
+    no_proxy: Option<String>,
+    /// Extra root certificate to trust, PEM or DER, can be given more than once
+    #[arg(long = "ca-cert", value_name = "FILE")]
+    ca_certs: Vec<PathBuf>,
+}
+
+// Where older versions kept the key, relative to the working directory
+const LEGACY_SECRET: &str = "secret.json";
+// Built into the binary so Spacepix can start from any directory
+const ICON: &[u8] = include_bytes!("../spacepix.png");
 
-fn load_icon(path: &str) -> egui::IconData {
+fn load_icon(bytes: &[u8]) -> egui::IconData {
     let (icon_rgba, icon_width, icon_height) = {
-        let image = image::open(path)
-            .expect("Failed to open icon path")
+        let image = image::load_from_memory(bytes)
+            .expect("Failed to decode icon")
             .into_rgba8();
         let (width, height) = image.dimensions();
         let rgba = image.into_raw();
@@ -23,27 +51,62 @@ fn load_icon(path: &str) -> egui::IconData {
 }
 
 fn main() -> eframe::Result {
-    env_logger::init();
+    let args = Args::parse();
+    let dirs = ConfigDirs::default();
+    let _log_guard = spacepix::init_logging(&dirs.log_dir());
     let native_options = eframe::NativeOptions {
         viewport: egui::ViewportBuilder::default()
             .with_inner_size([400.0, 300.0])
             .with_min_inner_size([300.0, 220.0])
-            .with_icon(load_icon("spacepix.png")),
+            .with_icon(load_icon(ICON)),
         ..Default::default()
     };
 
-    match fs::File::open(&SECRET) {
-        Ok(mut f) => {
-            let mut key = String::default();
-            f.read_to_string(&mut key).unwrap();
-            let key_json: serde_json::Value = serde_json::from_str(&key.as_str()).unwrap();//json::from(key);
-            let parser = Parser::new(key_json["key"].to_string());
-            Ok(eframe::run_native(
-            "Space Pix",
-            native_options,
-            Box::new(|cc| Ok(Box::new(spacepix::SpacePixUi::new(cc, parser)))),
-            )?)
-        },
-        Err(_) => panic!("Missing API key file..."),
+    if let Err(e) = dirs.migrate_key_file(Path::new(LEGACY_SECRET)) {
+        tracing::warn!("Failed to move {} into {}: {}", LEGACY_SECRET, dirs.config.display(), e);
+    }
+
+    // Older versions kept the key in plain text, encrypt it and remove the plain copy
+    let key_store = KeyStore::new(dirs.encrypted_key_file());
+    if let Err(e) = key_store.migrate_plaintext(&dirs.key_file()) {
+        tracing::warn!("Failed to encrypt {}: {}", dirs.key_file().display(), e);
     }
+
+    let mut profiles = KeyProfiles::new(dirs.config.clone());
+    if let Err(e) = profiles.load() {
+        tracing::warn!("Failed to load the key profiles: {}", e);
+    }
+
+    // Without a usable key start with DEMO_KEY and let the first run wizard sort it out
+    let mut parser = Parser::from_profiles(profiles);
+    let default_profile = parser.profiles().default_profile().to_string();
+    let mut key_error = parser.switch_profile(&default_profile).err();
+    if let Some(e) = &key_error {
+        tracing::warn!("Using {} because the {} profile's key couldn't be loaded: {}", Parser::DEMO_KEY, default_profile, e);
+    }
+
+    // The command line wins over the environment, both win over the profile for this run only
+    let key_override = args
+        .key
+        .or_else(|| std::env::var("SPACEPIX_API_KEY").ok())
+        .filter(|key| !key.trim().is_empty());
+    if let Some(key) = key_override {
+        parser.override_key(&key);
+        key_error = None;
+    }
+
+    // Proxy flags apply to this run only and win over Settings->Network
+    let proxy_override = ProxySettings {
+        http_proxy: args.http_proxy.unwrap_or_default(),
+        https_proxy: args.https_proxy.unwrap_or_default(),
+        socks_proxy: args.socks_proxy.unwrap_or_default(),
+        no_proxy: args.no_proxy.unwrap_or_default(),
+        ca_certificates: args.ca_certs,
+    };
+
+    eframe::run_native(
+        "Space Pix",
+        native_options,
+        Box::new(|cc| Ok(Box::new(spacepix::SpacePixUi::new(cc, parser, key_error, proxy_override)))),
+    )
 }
@@ -0,0 +1,227 @@
+use egui::{pos2, Color32, Pos2, Rect, Sense, Shape, Stroke, Vec2};
+
+// Very coarse (lon, lat) coastlines, just enough to tell where on Earth something is
+#[rustfmt::skip]
+const COASTLINES: [&[(f64, f64)]; 10] = [
+    // North America
+    &[
+        (-168.0, 66.0), (-162.0, 70.0), (-140.0, 70.0), (-125.0, 70.0), (-95.0, 72.0),
+        (-80.0, 73.0), (-62.0, 66.0), (-55.0, 52.0), (-66.0, 45.0), (-70.0, 41.0),
+        (-76.0, 35.0), (-81.0, 31.0), (-80.0, 25.0), (-82.0, 30.0), (-90.0, 30.0),
+        (-97.0, 26.0), (-97.0, 20.0), (-92.0, 18.0), (-87.0, 21.0), (-88.0, 16.0),
+        (-83.0, 10.0), (-78.0, 8.0), (-85.0, 11.0), (-92.0, 14.0), (-105.0, 20.0),
+        (-110.0, 23.0), (-112.0, 30.0), (-117.0, 33.0), (-124.0, 40.0), (-124.0, 48.0),
+        (-130.0, 55.0), (-140.0, 60.0), (-152.0, 58.0), (-165.0, 55.0), (-160.0, 60.0),
+        (-165.0, 63.0),
+    ],
+    // South America
+    &[
+        (-78.0, 8.0), (-72.0, 12.0), (-62.0, 10.0), (-50.0, 0.0), (-35.0, -7.0),
+        (-39.0, -15.0), (-48.0, -26.0), (-58.0, -38.0), (-65.0, -45.0), (-68.0, -55.0),
+        (-74.0, -50.0), (-72.0, -30.0), (-71.0, -18.0), (-81.0, -6.0), (-80.0, 1.0),
+    ],
+    // Europe and Asia
+    &[
+        (-10.0, 36.0), (-9.0, 43.0), (-2.0, 44.0), (-5.0, 48.0), (2.0, 51.0), (8.0, 54.0),
+        (10.0, 58.0), (5.0, 62.0), (15.0, 69.0), (28.0, 71.0), (40.0, 67.0), (60.0, 70.0),
+        (80.0, 73.0), (100.0, 77.0), (140.0, 72.0), (170.0, 70.0), (180.0, 67.0),
+        (160.0, 60.0), (155.0, 59.0), (142.0, 53.0), (140.0, 45.0), (130.0, 42.0),
+        (127.0, 35.0), (122.0, 40.0), (121.0, 31.0), (110.0, 20.0), (106.0, 10.0),
+        (100.0, 14.0), (98.0, 8.0), (104.0, 1.0), (98.0, 16.0), (92.0, 22.0), (80.0, 15.0),
+        (77.0, 8.0), (72.0, 21.0), (66.0, 25.0), (57.0, 25.0), (56.0, 27.0), (48.0, 30.0),
+        (57.0, 22.0), (52.0, 16.0), (43.0, 13.0), (35.0, 28.0), (35.0, 31.0), (28.0, 41.0),
+        (26.0, 38.0), (22.0, 37.0), (19.0, 42.0), (12.0, 44.0), (16.0, 39.0), (12.0, 38.0),
+        (8.0, 44.0), (3.0, 43.0), (-5.0, 36.0),
+    ],
+    // Africa
+    &[
+        (-17.0, 15.0), (-17.0, 21.0), (-10.0, 30.0), (-6.0, 36.0), (10.0, 37.0), (11.0, 33.0),
+        (20.0, 31.0), (32.0, 31.0), (35.0, 28.0), (43.0, 12.0), (51.0, 12.0), (40.0, -3.0),
+        (40.0, -15.0), (33.0, -26.0), (20.0, -35.0), (18.0, -30.0), (12.0, -17.0),
+        (14.0, -9.0), (9.0, -1.0), (9.0, 4.0), (-7.0, 4.0), (-13.0, 8.0),
+    ],
+    // Australia
+    &[
+        (114.0, -22.0), (122.0, -18.0), (130.0, -12.0), (137.0, -12.0), (142.0, -11.0),
+        (146.0, -19.0), (153.0, -25.0), (150.0, -37.0), (141.0, -38.0), (131.0, -31.0),
+        (115.0, -34.0),
+    ],
+    // Greenland
+    &[
+        (-73.0, 78.0), (-60.0, 82.0), (-30.0, 83.0), (-20.0, 75.0), (-40.0, 65.0),
+        (-50.0, 62.0), (-55.0, 70.0),
+    ],
+    // Great Britain
+    &[
+        (-5.0, 50.0), (1.0, 51.0), (0.0, 53.0), (-2.0, 57.0), (-5.0, 58.0), (-6.0, 55.0),
+        (-3.0, 54.0), (-5.0, 52.0),
+    ],
+    // Japan
+    &[
+        (130.0, 31.0), (135.0, 34.0), (140.0, 35.0), (142.0, 40.0), (141.0, 45.0),
+        (139.0, 40.0), (135.0, 36.0),
+    ],
+    // Madagascar
+    &[(44.0, -25.0), (47.0, -25.0), (50.0, -15.0), (49.0, -12.0), (44.0, -16.0)],
+    // Antarctica
+    &[(-180.0, -70.0), (-60.0, -64.0), (0.0, -70.0), (90.0, -66.0), (180.0, -70.0)],
+];
+
+/// Something to draw on the map, coordinates are (lon, lat) in degrees
+#[derive(Debug, Clone, PartialEq)]
+pub enum MapShape {
+    Point(f64, f64),
+    Polygon(Vec<(f64, f64)>),
+    Track(Vec<(f64, f64)>),
+}
+
+#[derive(Debug, Clone)]
+pub struct MapFeature {
+    pub shape: MapShape,
+    pub color: Color32,
+    pub label: String,
+}
+
+impl MapFeature {
+    pub fn new(shape: MapShape, color: Color32, label: impl Into<String>) -> Self {
+        Self {
+            shape,
+            color,
+            label: label.into(),
+        }
+    }
+}
+
+/// Equirectangular world map drawn with the egui painter, no map service needed
+pub struct WorldMap;
+
+impl WorldMap {
+    // How close the pointer has to be to a feature to hover or click it
+    const PICK_RADIUS: f32 = 8.0;
+
+    // Draw the map at the given width and return the index of the clicked feature, if any
+    pub fn show(ui: &mut egui::Ui, width: f32, features: &[MapFeature]) -> Option<usize> {
+        let (response, painter) =
+            ui.allocate_painter(Vec2::new(width, width / 2.0), Sense::click());
+        let rect = response.rect;
+
+        painter.rect_filled(rect, 0.0, Color32::from_rgb(20, 40, 80));
+        let grid = Stroke::new(0.5, Color32::from_rgb(50, 70, 110));
+        for lon in (-150..=150).step_by(30) {
+            painter.line_segment(
+                [project(lon as f64, 90.0, rect), project(lon as f64, -90.0, rect)],
+                grid,
+            );
+        }
+        for lat in (-60..=60).step_by(30) {
+            painter.line_segment(
+                [project(-180.0, lat as f64, rect), project(180.0, lat as f64, rect)],
+                grid,
+            );
+        }
+        let coast = Stroke::new(1.0, Color32::from_rgb(120, 160, 110));
+        for coastline in COASTLINES {
+            let points = coastline.iter().map(|(lon, lat)| project(*lon, *lat, rect)).collect();
+            painter.add(Shape::closed_line(points, coast));
+        }
+
+        for feature in features {
+            let stroke = Stroke::new(2.0, feature.color);
+            match &feature.shape {
+                MapShape::Point(lon, lat) => {
+                    painter.circle_filled(project(*lon, *lat, rect), 4.0, feature.color);
+                }
+                MapShape::Polygon(points) => {
+                    let points = points.iter().map(|(lon, lat)| project(*lon, *lat, rect)).collect();
+                    painter.add(Shape::closed_line(points, stroke));
+                }
+                MapShape::Track(points) => {
+                    for segment in split_antimeridian(points) {
+                        let points = segment.iter().map(|(lon, lat)| project(*lon, *lat, rect)).collect();
+                        painter.add(Shape::line(points, stroke));
+                    }
+                }
+            }
+        }
+
+        let hovered = response
+            .hover_pos()
+            .and_then(|pointer| Self::pick(pointer, rect, features));
+        if let Some(i) = hovered {
+            response.clone().on_hover_text(&features[i].label);
+        }
+        match response.clicked() {
+            true => hovered,
+            false => None,
+        }
+    }
+
+    fn pick(pointer: Pos2, rect: Rect, features: &[MapFeature]) -> Option<usize> {
+        features
+            .iter()
+            .enumerate()
+            .filter_map(|(i, feature)| {
+                let points: Vec<(f64, f64)> = match &feature.shape {
+                    MapShape::Point(lon, lat) => vec![(*lon, *lat)],
+                    MapShape::Polygon(points) | MapShape::Track(points) => points.clone(),
+                };
+                points
+                    .iter()
+                    .map(|(lon, lat)| project(*lon, *lat, rect).distance(pointer))
+                    .min_by(f32::total_cmp)
+                    .map(|distance| (i, distance))
+            })
+            .filter(|(_, distance)| *distance <= Self::PICK_RADIUS)
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(i, _)| i)
+    }
+}
+
+// Map a longitude/latitude onto the rect, -180..180 left to right and 90..-90 top to bottom
+pub fn project(lon: f64, lat: f64, rect: Rect) -> Pos2 {
+    pos2(
+        rect.left() + ((lon + 180.0) / 360.0) as f32 * rect.width(),
+        rect.top() + ((90.0 - lat) / 180.0) as f32 * rect.height(),
+    )
+}
+
+// Break a track wherever it wraps around the map so it isn't drawn straight across
+pub fn split_antimeridian(points: &[(f64, f64)]) -> Vec<Vec<(f64, f64)>> {
+    let mut segments: Vec<Vec<(f64, f64)>> = Vec::default();
+    let mut current: Vec<(f64, f64)> = Vec::default();
+    for point in points {
+        if let Some(last) = current.last() {
+            if (point.0 - last.0).abs() > 180.0 {
+                segments.push(std::mem::take(&mut current));
+            }
+        }
+        current.push(*point);
+    }
+    if !current.is_empty() {
+        segments.push(current);
+    }
+    segments
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{project, split_antimeridian};
+    use egui::{pos2, Rect};
+
+    #[test]
+    fn test_project() {
+        let rect = Rect::from_min_max(pos2(0.0, 0.0), pos2(360.0, 180.0));
+        assert_eq!(project(0.0, 0.0, rect), pos2(180.0, 90.0));
+        assert_eq!(project(-180.0, 90.0, rect), pos2(0.0, 0.0));
+        assert_eq!(project(180.0, -90.0, rect), pos2(360.0, 180.0));
+    }
+
+    #[test]
+    fn test_split_antimeridian() {
+        let track = [(170.0, 0.0), (179.0, 1.0), (-179.0, 2.0), (-170.0, 3.0)];
+        assert_eq!(
+            split_antimeridian(&track),
+            vec![vec![(170.0, 0.0), (179.0, 1.0)], vec![(-179.0, 2.0), (-170.0, 3.0)]]
+        );
+    }
+}
@@ -0,0 +1,167 @@
+use crate::errors::TleError;
+use chrono::NaiveDateTime;
+
+// WGS84 ellipsoid, used to turn positions into latitude and altitude above the surface
+const EARTH_RADIUS_KM: f64 = 6378.137;
+const EARTH_FLATTENING: f64 = 1.0 / 298.257223563;
+
+/// Where a satellite is over the Earth, lat/lon are signed degrees and altitude is in km
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct GroundPosition {
+    pub lat: f64,
+    pub lon: f64,
+    pub altitude: f64,
+}
+
+/// SGP4 propagator for one two-line element set
+pub struct Orbit {
+    elements: sgp4::Elements,
+    constants: sgp4::Constants,
+}
+
+impl Orbit {
+    pub fn from_tle(line1: &str, line2: &str) -> Result<Self, TleError> {
+        let elements = sgp4::Elements::from_tle(None, line1.trim().as_bytes(), line2.trim().as_bytes())?;
+        // TLEs are fit with WGS72, so propagate them the way the AFSPC does
+        let constants = sgp4::Constants::from_elements_afspc_compatibility_mode(&elements)?;
+        Ok(Self {
+            elements,
+            constants,
+        })
+    }
+
+    pub fn epoch(&self) -> NaiveDateTime {
+        self.elements.datetime
+    }
+
+    // Minutes per revolution, from the mean motion in revolutions per day
+    pub fn period_minutes(&self) -> f64 {
+        1440.0 / self.elements.mean_motion
+    }
+
+    // Position in km in the TEME frame, minutes after the element set's epoch
+    pub fn teme_position(&self, minutes_since_epoch: f64) -> Result<[f64; 3], TleError> {
+        let prediction = self.constants.propagate(sgp4::MinutesSinceEpoch(minutes_since_epoch))?;
+        Ok(prediction.position)
+    }
+
+    pub fn ground_position(&self, time: NaiveDateTime) -> Result<GroundPosition, TleError> {
+        let minutes = (time - self.elements.datetime).num_milliseconds() as f64 / 60_000.0;
+        Ok(teme_to_ground(self.teme_position(minutes)?, time))
+    }
+
+    // Positions every step_minutes from start to end, points that fail to propagate are left out
+    pub fn ground_track(&self, start: NaiveDateTime, end: NaiveDateTime, step_minutes: i64) -> Vec<GroundPosition> {
+        let mut track: Vec<GroundPosition> = Vec::default();
+        let mut time = start;
+        while time <= end {
+            if let Ok(position) = self.ground_position(time) {
+                track.push(position);
+            }
+            time += chrono::Duration::minutes(step_minutes.max(1));
+        }
+        track
+    }
+}
+
+// Greenwich mean sidereal time in radians, how far the Earth has turned under the TEME frame
+pub fn sidereal_time(time: NaiveDateTime) -> f64 {
+    sgp4::iau_epoch_to_sidereal_time(sgp4::julian_years_since_j2000(&time))
+}
+
+// Rotate a TEME position into Earth fixed coordinates and find the point on the ground below it
+pub fn teme_to_ground(position: [f64; 3], time: NaiveDateTime) -> GroundPosition {
+    let theta = sidereal_time(time);
+    let x = theta.cos() * position[0] + theta.sin() * position[1];
+    let y = -theta.sin() * position[0] + theta.cos() * position[1];
+    let z = position[2];
+
+    // Geodetic latitude doesn't have a closed form, a few iterations gets well under a meter
+    let e2 = EARTH_FLATTENING * (2.0 - EARTH_FLATTENING);
+    let p = x.hypot(y);
+    let mut lat = z.atan2(p * (1.0 - e2));
+    let mut altitude = 0.0;
+    for _ in 0..5 {
+        let n = EARTH_RADIUS_KM / (1.0 - e2 * lat.sin().powi(2)).sqrt();
+        // Written so it still works straight over the poles, where cos(lat) is zero
+        altitude = p * lat.cos() + (z + e2 * n * lat.sin()) * lat.sin() - n;
+        lat = z.atan2(p * (1.0 - e2 * n / (n + altitude)));
+    }
+
+    GroundPosition {
+        lat: lat.to_degrees(),
+        lon: y.atan2(x).to_degrees(),
+        altitude,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{sidereal_time, teme_to_ground, Orbit};
+    use chrono::NaiveDate;
+
+    // Test case 00005 from Vallado's "Revisiting Spacetrack Report #3"
+    const VANGUARD_LINE1: &str = "1 00005U 58002B   00179.78495062  .00000023  00000-0  28098-4 0  4753";
+    const VANGUARD_LINE2: &str = "2 00005  34.2682 348.7242 1859667 331.7664  19.3264 10.82419157413667";
+    const ISS_LINE1: &str = "1 25544U 98067A   08264.51782528 -.00002182  00000-0 -11606-4 0  2927";
+    const ISS_LINE2: &str = "2 25544  51.6416 247.4627 0006703 130.5360 325.0288 15.72125391563537";
+
+    fn assert_close(actual: f64, expected: f64, tolerance: f64) {
+        assert!((actual - expected).abs() <= tolerance, "{} is not within {} of {}", actual, tolerance, expected);
+    }
+
+    #[test]
+    fn test_propagation() {
+        let orbit = Orbit::from_tle(VANGUARD_LINE1, VANGUARD_LINE2).unwrap();
+        for (minutes, expected) in [
+            (0.0, [7022.46529266, -1400.08296755, 0.03995155]),
+            (360.0, [-7154.03120202, -3783.17682504, -3536.19412294]),
+        ] {
+            let position = orbit.teme_position(minutes).unwrap();
+            for axis in 0..3 {
+                assert_close(position[axis], expected[axis], 1e-5);
+            }
+        }
+        assert_close(orbit.period_minutes(), 133.04, 0.01);
+        assert!(Orbit::from_tle("1 00005U", VANGUARD_LINE2).is_err());
+    }
+
+    #[test]
+    fn test_sidereal_time() {
+        // 280.46061837 degrees at J2000
+        let j2000 = NaiveDate::from_ymd_opt(2000, 1, 1).unwrap().and_hms_opt(12, 0, 0).unwrap();
+        assert_close(sidereal_time(j2000).to_degrees(), 280.46061837, 1e-6);
+    }
+
+    #[test]
+    fn test_teme_to_ground() {
+        let time = NaiveDate::from_ymd_opt(2000, 1, 1).unwrap().and_hms_opt(12, 0, 0).unwrap();
+        let theta = sidereal_time(time);
+
+        // Straight above the equator at the prime meridian
+        let equator = teme_to_ground([7000.0 * theta.cos(), 7000.0 * theta.sin(), 0.0], time);
+        assert_close(equator.lat, 0.0, 1e-9);
+        assert_close(equator.lon, 0.0, 1e-9);
+        assert_close(equator.altitude, 7000.0 - 6378.137, 1e-6);
+
+        // Straight above the north pole, where the Earth is flattened
+        let pole = teme_to_ground([0.0, 0.0, 7000.0], time);
+        assert_close(pole.lat, 90.0, 1e-6);
+        assert_close(pole.altitude, 7000.0 - 6356.752, 1e-3);
+    }
+
+    #[test]
+    fn test_ground_track() {
+        let orbit = Orbit::from_tle(ISS_LINE1, ISS_LINE2).unwrap();
+        let start = orbit.epoch();
+        let track = orbit.ground_track(start, start + chrono::Duration::minutes(90), 1);
+
+        assert_eq!(track.len(), 91);
+        // Just past the northernmost point of its orbit at the epoch, over the Pacific and heading south
+        assert_close(track[0].lat, 51.5, 0.3);
+        assert_close(track[0].lon, 160.1, 0.5);
+        assert!(track[1].lat < track[0].lat);
+        assert!(track.iter().all(|position| position.lat.abs() <= 52.0));
+        assert!(track.iter().all(|position| (340.0..=380.0).contains(&position.altitude)));
+    }
+}
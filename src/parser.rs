@@ -1,59 +1,221 @@
-use crate::Urls;
+use crate::{
+    validate_api_key_blocking, CadQuery, EarthLocation, EonetQuery, EpicCollection,
+    EpicImage, EpicImageFormat, ExoplanetQuery, MarsPhotoDate, MarsPhotoQuery, MarsRover,
+    NIVLSearch, SentryLookup, TechTransferCategory, TleSearch, Urls,
+};
 use crate::errors::ApiKeyError;
-use json::object;
+use crate::keystore::KeyProfiles;
+use crate::RateLimit;
+use std::collections::BTreeMap;
+use std::io::Read;
+use std::sync::{Arc, Mutex};
 use std::{fs, path::Path};
-use std::io::{Read, Write};
 
 
 #[derive(Clone, serde::Serialize, serde::Deserialize, std::fmt::Debug)]
 pub struct Parser {
     pub urls: Urls,
+    // Kept out of the saved app state, the key only lives in the encrypted key stores
+    #[serde(skip)]
     key: String,
+    #[serde(skip)]
+    profiles: KeyProfiles,
+    #[serde(skip)]
+    profile: String, // The profile the key came from
+    #[serde(skip)]
+    key_overridden: bool, // The key came from --key or SPACEPIX_API_KEY for this run only
+    // Shared between the clones handed to each window, keyed by profile
+    #[serde(skip)]
+    rate_limits: Arc<Mutex<BTreeMap<String, RateLimit>>>,
 }
 
 impl Default for Parser {
+    // Only a placeholder until main() hands over the parser with the profile's key, so it mustn't
+    // read the key stores
     fn default() -> Self {
-        Self {
-            urls: Urls::default(),
-            key: {
-                // println!("{}", Self::read_key_file(fs::File::open("secret.json").unwrap()).unwrap());
-                Self::read_key_file(fs::File::open("secret.json").unwrap()).unwrap()
-            },
-        }
+        Self::new(String::from(Self::DEMO_KEY))
     }
 }
 
 impl Parser {
+    pub const DEMO_KEY: &'static str = "DEMO_KEY";
+    // Where requests made with an overriding key are counted
+    pub const OVERRIDE_PROFILE: &'static str = "--key";
+
+    // Touches no files, the profiles only read or create anything once a key is saved or loaded
     pub fn new(key: String) -> Self {
         Self {
             urls: Urls::default(),
             key,
+            profiles: KeyProfiles::default(),
+            profile: String::from(KeyProfiles::DEFAULT_PROFILE),
+            key_overridden: false,
+            rate_limits: Arc::default(),
+        }
+    }
+
+    pub fn from_key_file(secret_path: &Path) -> Result<Self, ApiKeyError> {
+        Ok(Self::new(Self::read_key_file(fs::File::open(secret_path)?)?))
+    }
+
+    // Starts on DEMO_KEY, switch_profile() loads a profile's key
+    pub fn from_profiles(profiles: KeyProfiles) -> Self {
+        Self {
+            urls: Urls::default(),
+            key: String::from(Self::DEMO_KEY),
+            profile: profiles.default_profile().to_string(),
+            profiles,
+            key_overridden: false,
+            rate_limits: Arc::default(),
         }
     }
 
-    pub fn set_api_key(&mut self, secret_path: &Path, key: String) -> Result<(), ApiKeyError> {
-        match fs::File::create(secret_path) {
-            Ok(mut f) => {
-                let json_buff = object! {key: key};
-                let _ = f.write(json_buff.to_string().as_bytes());
+    // Personal keys from api.nasa.gov are 40 letters and digits, anything else is a typo
+    pub fn is_key_well_formed(key: &str) -> bool {
+        key == Self::DEMO_KEY || (key.len() == 40 && key.chars().all(|c| c.is_ascii_alphanumeric()))
+    }
+
+    pub fn profiles(&self) -> &KeyProfiles {
+        &self.profiles
+    }
+
+    pub fn profile(&self) -> &str {
+        &self.profile
+    }
+
+    pub fn is_key_overridden(&self) -> bool {
+        self.key_overridden
+    }
+
+    // Use the profile's key from now on, a profile without a usable key falls back to DEMO_KEY
+    pub fn switch_profile(&mut self, name: &str) -> Result<(), ApiKeyError> {
+        let store = self
+            .profiles
+            .store(name)
+            .ok_or_else(|| ApiKeyError::UnknownProfile(name.to_string()))?;
+        self.profile = name.to_string();
+        self.key_overridden = false;
+        match store.load() {
+            Ok(key) => {
+                self.key = key;
                 Ok(())
             }
-            Err(e) => Err(ApiKeyError::KeyFile(e)),
+            Err(e) => {
+                self.key = String::from(Self::DEMO_KEY);
+                Err(e)
+            }
+        }
+    }
+
+    // Use a key given on the command line or in the environment without saving it anywhere
+    pub fn override_key(&mut self, key: &str) {
+        self.key = key.trim().to_string();
+        self.key_overridden = true;
+    }
+
+    // Unlock the current profile's key when it was saved with a passphrase
+    pub fn unlock(&mut self, passphrase: &str) -> Result<(), ApiKeyError> {
+        self.set_passphrase(Some(passphrase.to_string()));
+        let profile = self.profile.clone();
+        self.switch_profile(&profile)
+    }
+
+    // The passphrase the current profile's next key is saved with, None ties it to this machine
+    pub fn set_passphrase(&mut self, passphrase: Option<String>) {
+        if let Some(store) = self.profiles.store_mut(&self.profile) {
+            store.set_passphrase(passphrase);
+        }
+    }
+
+    pub fn add_profile(&mut self, name: &str) -> Result<(), ApiKeyError> {
+        self.profiles.add(name)
+    }
+
+    // Removing the profile in use moves over to the default profile
+    pub fn remove_profile(&mut self, name: &str) -> Result<(), ApiKeyError> {
+        self.profiles.remove(name)?;
+        if self.profile == name && !self.key_overridden {
+            let default = self.profiles.default_profile().to_string();
+            match self.switch_profile(&default) {
+                // A default profile without a key just means DEMO_KEY, the removal itself went fine
+                Err(ApiKeyError::KeyFile(e)) if e.kind() == std::io::ErrorKind::NotFound => {}
+                result => return result,
+            }
+        }
+        Ok(())
+    }
+
+    pub fn set_default_profile(&mut self, name: &str) -> Result<(), ApiKeyError> {
+        self.profiles.set_default(name)
+    }
+
+    // Save the key to the current profile and start using it
+    pub fn set_api_key(&mut self, key: String) -> Result<(), ApiKeyError> {
+        self.profiles
+            .store(&self.profile)
+            .ok_or_else(|| ApiKeyError::UnknownProfile(self.profile.clone()))?
+            .save(&key)?;
+        self.key = key;
+        self.key_overridden = false;
+        Ok(())
+    }
+
+    // Delete the current profile's key and go back to DEMO_KEY
+    pub fn forget_api_key(&mut self) -> Result<(), ApiKeyError> {
+        if let Some(store) = self.profiles.store(&self.profile) {
+            store.forget()?;
+        }
+        self.key = String::from(Self::DEMO_KEY);
+        self.key_overridden = false;
+        Ok(())
+    }
+
+    // Check a new key with NASA before saving it, so a typo doesn't replace a working key
+    pub fn change_api_key_blocking(&mut self, key: &str) -> Result<(), ApiKeyError> {
+        let key = key.trim();
+        if !Self::is_key_well_formed(key) {
+            return Err(ApiKeyError::InvalidApiKey());
+        }
+        // DEMO_KEY always works and has few requests to spare
+        if key != Self::DEMO_KEY {
+            validate_api_key_blocking(self, key)?;
+        }
+        self.set_api_key(key.to_string())
+    }
+
+    // Count a request made with the current key, along with the rate limit NASA sent back
+    pub fn record_request(&self, headers: &reqwest::header::HeaderMap) {
+        let profile = match self.key_overridden {
+            true => Self::OVERRIDE_PROFILE,
+            false => &self.profile,
+        };
+        if let Ok(mut rate_limits) = self.rate_limits.lock() {
+            rate_limits.entry(profile.to_string()).or_default().record(headers);
         }
     }
 
+    pub fn rate_limit(&self, profile: &str) -> RateLimit {
+        self.rate_limits
+            .lock()
+            .ok()
+            .and_then(|rate_limits| rate_limits.get(profile).copied())
+            .unwrap_or_default()
+    }
+
     pub fn read_key_file(mut file: fs::File) -> Result<String, ApiKeyError> {
         let mut key = String::default();
-        match file.read_to_string(&mut key) {
-            Ok(_) => {
-                // let key_json = json::from(key);
-                let key_json: serde_json::Value = serde_json::from_str(&key.as_str()).unwrap();
-                Ok(key_json["key"].to_string())
-            },
-            Err(e) => Err(ApiKeyError::KeyFile(e))
+        file.read_to_string(&mut key)?;
+        let key_json: serde_json::Value = serde_json::from_str(&key)?;
+        match key_json["key"].as_str() {
+            Some(key) if !key.trim().is_empty() => Ok(key.trim().to_string()),
+            _ => Err(ApiKeyError::MissingKey()),
         }
     }
 
+    pub fn key_check_url(&self, key: &str) -> String {
+        format!("{}{}", self.urls.key_check, key)
+    }
+
     pub fn apod_url(&self) -> String {
         format!("{}{}", self.urls.apod, self.key)
     }
@@ -69,14 +231,244 @@ impl Parser {
         )
     }
 
+    pub fn wsa_enlil_url(&self, start_date: &str, end_date: &str) -> String {
+        format!(
+            "{}{}",
+            self.urls
+                .wsa_enlil
+                .replace("START_DATE", start_date)
+                .replace("END_DATE", end_date),
+            self.key
+        )
+    }
+
+    // The Image and Video Library doesn't take an API key
+    pub fn nivl_search_url(&self, search: &NIVLSearch) -> String {
+        match reqwest::Url::parse_with_params(&self.urls.nivl_search, search.params()) {
+            Ok(url) => url.to_string(),
+            Err(_) => self.urls.nivl_search.clone(),
+        }
+    }
+
+    pub fn nivl_asset_url(&self, nasa_id: &str) -> String {
+        Self::append_path_segment(&self.urls.nivl_asset, nasa_id)
+    }
+
+    pub fn nivl_metadata_url(&self, nasa_id: &str) -> String {
+        Self::append_path_segment(&self.urls.nivl_metadata, nasa_id)
+    }
+
+    pub fn nivl_album_url(&self, album: &str, page: u32) -> String {
+        format!(
+            "{}?page={}",
+            Self::append_path_segment(&self.urls.nivl_album, album),
+            page
+        )
+    }
+
+    // NASA ids can contain spaces, so let Url do the escaping
+    fn append_path_segment(base: &str, segment: &str) -> String {
+        match reqwest::Url::parse(base) {
+            Ok(mut url) => {
+                if let Ok(mut segments) = url.path_segments_mut() {
+                    segments.push(segment);
+                }
+                url.to_string()
+            }
+            Err(_) => format!("{}/{}", base, segment),
+        }
+    }
+
+    pub fn mars_photos_url(&self, query: &MarsPhotoQuery) -> String {
+        let mut params = match &query.date {
+            MarsPhotoDate::Sol(sol) => format!("sol={}", sol),
+            MarsPhotoDate::EarthDate(date) => format!("earth_date={}", date.trim()),
+        };
+        if let Some(camera) = &query.camera {
+            params.push_str(&format!("&camera={}", camera.to_lowercase()));
+        }
+        params.push_str(&format!("&page={}", query.page.max(1)));
+        format!(
+            "{}{}",
+            self.urls
+                .mars_photos
+                .replace("ROVER", query.rover.name())
+                .replace("QUERY", &params),
+            self.key
+        )
+    }
+
+    pub fn mars_manifest_url(&self, rover: MarsRover) -> String {
+        format!("{}{}", self.urls.mars_manifest.replace("ROVER", rover.name()), self.key)
+    }
+
+    pub fn epic_url(&self, collection: EpicCollection, date: &str) -> String {
+        let date = match date.trim() {
+            "" => String::default(),
+            date => format!("/date/{}", date),
+        };
+        format!(
+            "{}{}",
+            self.urls
+                .epic
+                .replace("COLLECTION", collection.name())
+                .replace("/DATE", &date),
+            self.key
+        )
+    }
+
+    pub fn epic_image_url(
+        &self,
+        collection: EpicCollection,
+        image: &EpicImage,
+        format: EpicImageFormat,
+    ) -> String {
+        format!("{}{}", self.epic_archive_path(collection, image, format), self.key)
+    }
+
+    // Names the image for egui's image cache, which keeps its URIs around and logs them, so it
+    // never sees the key
+    pub fn epic_image_uri(
+        &self,
+        collection: EpicCollection,
+        image: &EpicImage,
+        format: EpicImageFormat,
+    ) -> String {
+        without_key(&self.epic_archive_path(collection, image, format))
+    }
+
+    fn epic_archive_path(
+        &self,
+        collection: EpicCollection,
+        image: &EpicImage,
+        format: EpicImageFormat,
+    ) -> String {
+        let (folder, extension) = format.path();
+        self.urls
+            .epic_archive
+            .replace("COLLECTION", collection.name())
+            .replace("DATE_PATH", &image.date_path())
+            .replace("FORMAT", folder)
+            .replace("IMAGE", &image.image)
+            .replace("EXTENSION", extension)
+    }
+
+    // EONET doesn't take an API key
+    pub fn eonet_events_url(&self, query: &EonetQuery) -> String {
+        match reqwest::Url::parse_with_params(&self.urls.eonet_events, query.params()) {
+            Ok(url) => url.to_string(),
+            Err(_) => self.urls.eonet_events.clone(),
+        }
+    }
+
+    pub fn sentry_object_url(&self, lookup: &SentryLookup) -> String {
+        let param = match lookup {
+            SentryLookup::Des(des) => ("des", des.trim()),
+            SentryLookup::Spk(spk) => ("spk", spk.trim()),
+        };
+        match reqwest::Url::parse_with_params(&self.urls.sentry, [param]) {
+            Ok(url) => url.to_string(),
+            Err(_) => self.urls.sentry.clone(),
+        }
+    }
+
+    pub fn fireball_url(&self, limit: u32, date_min: &str) -> String {
+        let mut params = vec![("limit", limit.to_string())];
+        if !date_min.trim().is_empty() {
+            params.push(("date-min", date_min.trim().to_string()));
+        }
+        match reqwest::Url::parse_with_params(&self.urls.fireball, params) {
+            Ok(url) => url.to_string(),
+            Err(_) => self.urls.fireball.clone(),
+        }
+    }
+
+    pub fn cad_url(&self, query: &CadQuery) -> String {
+        match reqwest::Url::parse_with_params(&self.urls.cad, query.params()) {
+            Ok(url) => url.to_string(),
+            Err(_) => self.urls.cad.clone(),
+        }
+    }
+
+    // The Exoplanet Archive doesn't take an api key, the ADQL goes in the query string
+    pub fn exoplanet_url(&self, query: &ExoplanetQuery) -> String {
+        let params = [("query", query.adql()), ("format", String::from("json"))];
+        match reqwest::Url::parse_with_params(&self.urls.exoplanet_tap, params) {
+            Ok(url) => url.to_string(),
+            Err(_) => self.urls.exoplanet_tap.clone(),
+        }
+    }
+
+    pub fn earth_assets_url(&self, location: &EarthLocation, date: &str) -> String {
+        format!("{}{}", self.urls.earth_assets.replace("QUERY", &location.params(date)), self.key)
+    }
+
+    pub fn earth_imagery_url(&self, location: &EarthLocation, date: &str) -> String {
+        format!("{}{}", self.urls.earth_imagery.replace("QUERY", &location.params(date)), self.key)
+    }
+
+    // Like epic_image_uri(), the name egui's image cache gets instead of the keyed url
+    pub fn earth_imagery_uri(&self, location: &EarthLocation, date: &str) -> String {
+        without_key(&self.urls.earth_imagery.replace("QUERY", &location.params(date)))
+    }
+
+    // TechTransfer takes the search terms as the whole query string, ahead of the api key
+    pub fn techtransfer_url(&self, category: TechTransferCategory, query: &str) -> String {
+        format!(
+            "{}{}",
+            self.urls
+                .techtransfer
+                .replace("CATEGORY", category.name())
+                .replace("QUERY", &Self::percent_encode(query.trim())),
+            self.key
+        )
+    }
+
+    fn percent_encode(text: &str) -> String {
+        text.bytes()
+            .map(|byte| match byte {
+                b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => (byte as char).to_string(),
+                _ => format!("%{:02X}", byte),
+            })
+            .collect()
+    }
+
+    // The TLE API doesn't take an API key
+    pub fn tle_search_url(&self, search: &str, page: u32) -> String {
+        let params = [
+            ("search", search.trim().to_string()),
+            ("page", page.max(1).to_string()),
+            ("page-size", TleSearch::PAGE_SIZE.to_string()),
+        ];
+        match reqwest::Url::parse_with_params(&self.urls.tle_search, params) {
+            Ok(url) => url.to_string(),
+            Err(_) => self.urls.tle_search.clone(),
+        }
+    }
+
+    pub fn tle_url(&self, satellite_id: u32) -> String {
+        self.urls.tle_record.replace("SATELLITE_ID", &satellite_id.to_string())
+    }
+
     pub fn get_api_key(&self) -> String {
         self.key.clone()
     }
 }
 
+// A url template with its trailing api_key= parameter dropped
+fn without_key(url: &str) -> String {
+    url.trim_end_matches("api_key=").trim_end_matches(['?', '&']).to_string()
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::Parser;
+    use crate::{
+        CadQuery, EarthLocation, EonetQuery, EpicCollection, EpicImage, EpicImageFormat,
+        ExoplanetQuery, MarsPhotoDate, MarsPhotoQuery, MarsRover, NIVLSearch, Parser,
+        SentryLookup, TechTransferCategory,
+    };
+    use crate::errors::ApiKeyError;
+    use crate::keystore::KeyProfiles;
 
     #[test]
     fn test_apod_url() {
@@ -87,4 +479,205 @@ mod tests {
     fn test_neows_url() {
         assert_eq!(Parser::new(String::from("DEMO_KEY")).neows_url("2020-10-10"), "https://api.nasa.gov/neo/rest/v1/feed?start_date=2020-10-10&end_date=2020-10-10&api_key=DEMO_KEY");
     }
+
+    #[test]
+    fn test_wsa_enlil_url() {
+        assert_eq!(Parser::new(String::from("DEMO_KEY")).wsa_enlil_url("2024-01-01", "2024-01-31"), "https://api.nasa.gov/DONKI/WSAEnlilSimulations?startDate=2024-01-01&endDate=2024-01-31&api_key=DEMO_KEY");
+    }
+
+    #[test]
+    fn test_nivl_search_url() {
+        let search = NIVLSearch {
+            q: String::from("apollo 11"),
+            media_type: String::from("image"),
+            year_start: String::from("1969"),
+            ..Default::default()
+        };
+        assert_eq!(Parser::new(String::from("DEMO_KEY")).nivl_search_url(&search), "https://images-api.nasa.gov/search?q=apollo+11&media_type=image&year_start=1969&page=1&page_size=24");
+    }
+
+    #[test]
+    fn test_nivl_asset_url() {
+        let parser = Parser::new(String::from("DEMO_KEY"));
+        assert_eq!(parser.nivl_asset_url("as11-40-5874"), "https://images-api.nasa.gov/asset/as11-40-5874");
+        assert_eq!(parser.nivl_metadata_url("KSC 1969"), "https://images-api.nasa.gov/metadata/KSC%201969");
+    }
+
+    #[test]
+    fn test_nivl_album_url() {
+        assert_eq!(Parser::new(String::from("DEMO_KEY")).nivl_album_url("Apollo-at-50", 2), "https://images-api.nasa.gov/album/Apollo-at-50?page=2");
+    }
+
+    #[test]
+    fn test_mars_photos_url() {
+        let parser = Parser::new(String::from("DEMO_KEY"));
+        let query = MarsPhotoQuery {
+            rover: MarsRover::Curiosity,
+            date: MarsPhotoDate::Sol(1000),
+            camera: Some(String::from("FHAZ")),
+            page: 2,
+        };
+        assert_eq!(parser.mars_photos_url(&query), "https://api.nasa.gov/mars-photos/api/v1/rovers/curiosity/photos?sol=1000&camera=fhaz&page=2&api_key=DEMO_KEY");
+
+        let query = MarsPhotoQuery {
+            rover: MarsRover::Perseverance,
+            date: MarsPhotoDate::EarthDate(String::from("2021-02-18")),
+            camera: None,
+            page: 1,
+        };
+        assert_eq!(parser.mars_photos_url(&query), "https://api.nasa.gov/mars-photos/api/v1/rovers/perseverance/photos?earth_date=2021-02-18&page=1&api_key=DEMO_KEY");
+        assert_eq!(parser.mars_manifest_url(MarsRover::Spirit), "https://api.nasa.gov/mars-photos/api/v1/manifests/spirit?api_key=DEMO_KEY");
+    }
+
+    #[test]
+    fn test_epic_urls() {
+        let parser = Parser::new(String::from("DEMO_KEY"));
+        let image = EpicImage {
+            image: String::from("epic_1b_20190530011359"),
+            date: String::from("2019-05-30 00:50:27"),
+            ..Default::default()
+        };
+        assert_eq!(parser.epic_url(EpicCollection::Natural, "2019-05-30"), "https://api.nasa.gov/EPIC/api/natural/date/2019-05-30?api_key=DEMO_KEY");
+        assert_eq!(parser.epic_url(EpicCollection::Enhanced, ""), "https://api.nasa.gov/EPIC/api/enhanced?api_key=DEMO_KEY");
+        assert_eq!(parser.epic_image_url(EpicCollection::Natural, &image, EpicImageFormat::Png), "https://api.nasa.gov/EPIC/archive/natural/2019/05/30/png/epic_1b_20190530011359.png?api_key=DEMO_KEY");
+        assert_eq!(parser.epic_image_url(EpicCollection::Enhanced, &image, EpicImageFormat::Thumbs), "https://api.nasa.gov/EPIC/archive/enhanced/2019/05/30/thumbs/epic_1b_20190530011359.jpg?api_key=DEMO_KEY");
+        assert_eq!(parser.epic_image_uri(EpicCollection::Natural, &image, EpicImageFormat::Jpg), "https://api.nasa.gov/EPIC/archive/natural/2019/05/30/jpg/epic_1b_20190530011359.jpg");
+    }
+
+    #[test]
+    fn test_eonet_events_url() {
+        let query = EonetQuery {
+            category: Some(String::from("wildfires")),
+            days: 10,
+            ..Default::default()
+        };
+        assert_eq!(Parser::new(String::from("DEMO_KEY")).eonet_events_url(&query), "https://eonet.gsfc.nasa.gov/api/v3/events?status=open&days=10&category=wildfires");
+    }
+
+    #[test]
+    fn test_sentry_object_url() {
+        let parser = Parser::new(String::from("DEMO_KEY"));
+        assert_eq!(parser.sentry_object_url(&SentryLookup::Spk(String::from("2029075"))), "https://ssd-api.jpl.nasa.gov/sentry.api?spk=2029075");
+        assert_eq!(parser.sentry_object_url(&SentryLookup::Des(String::from("2023 DW"))), "https://ssd-api.jpl.nasa.gov/sentry.api?des=2023+DW");
+    }
+
+    #[test]
+    fn test_fireball_url() {
+        let parser = Parser::new(String::from("DEMO_KEY"));
+        assert_eq!(parser.fireball_url(20, ""), "https://ssd-api.jpl.nasa.gov/fireball.api?limit=20");
+        assert_eq!(parser.fireball_url(20, "2024-01-01"), "https://ssd-api.jpl.nasa.gov/fireball.api?limit=20&date-min=2024-01-01");
+    }
+
+    #[test]
+    fn test_cad_url() {
+        let query = CadQuery {
+            date_min: String::from("2024-01-01"),
+            date_max: String::from("2024-12-31"),
+            dist_max: String::from("1LD"),
+            include_comets: true,
+        };
+        assert_eq!(Parser::new(String::from("DEMO_KEY")).cad_url(&query), "https://ssd-api.jpl.nasa.gov/cad.api?date-min=2024-01-01&date-max=2024-12-31&dist-max=1LD&comet=true&fullname=true");
+    }
+
+    #[test]
+    fn test_exoplanet_url() {
+        let query = ExoplanetQuery {
+            limit: 5,
+            ..Default::default()
+        };
+        assert_eq!(Parser::new(String::from("DEMO_KEY")).exoplanet_url(&query), "https://exoplanetarchive.ipac.caltech.edu/TAP/sync?query=select+top+5+pl_name%2Chostname%2Cdiscoverymethod%2Cdisc_year%2Cpl_rade%2Cpl_bmasse%2Cpl_orbper+from+pscomppars+order+by+disc_year+desc&format=json");
+    }
+
+    #[test]
+    fn test_earth_urls() {
+        let parser = Parser::new(String::from("DEMO_KEY"));
+        let location = EarthLocation {
+            name: String::from("Houston"),
+            lat: 29.78,
+            lon: -95.33,
+            dim: 0.15,
+        };
+        assert_eq!(parser.earth_assets_url(&location, "2018-01-01"), "https://api.nasa.gov/planetary/earth/assets?lon=-95.33&lat=29.78&date=2018-01-01&dim=0.15&api_key=DEMO_KEY");
+        assert_eq!(parser.earth_imagery_url(&location, "2018-01-01"), "https://api.nasa.gov/planetary/earth/imagery?lon=-95.33&lat=29.78&date=2018-01-01&dim=0.15&api_key=DEMO_KEY");
+        assert_eq!(parser.earth_imagery_uri(&location, "2018-01-01"), "https://api.nasa.gov/planetary/earth/imagery?lon=-95.33&lat=29.78&date=2018-01-01&dim=0.15");
+    }
+
+    #[test]
+    fn test_techtransfer_url() {
+        let parser = Parser::new(String::from("DEMO_KEY"));
+        assert_eq!(parser.techtransfer_url(TechTransferCategory::Patent, "engine"), "https://api.nasa.gov/techtransfer/patent/?engine&api_key=DEMO_KEY");
+        assert_eq!(parser.techtransfer_url(TechTransferCategory::Software, " solar & wind "), "https://api.nasa.gov/techtransfer/software/?solar%20%26%20wind&api_key=DEMO_KEY");
+    }
+
+    #[test]
+    fn test_tle_urls() {
+        let parser = Parser::new(String::from("DEMO_KEY"));
+        assert_eq!(parser.tle_search_url(" hubble space ", 2), "https://tle.ivanstanojevic.me/api/tle?search=hubble+space&page=2&page-size=20");
+        assert_eq!(parser.tle_url(25544), "https://tle.ivanstanojevic.me/api/tle/25544");
+    }
+
+    #[test]
+    fn test_read_key_file() {
+        let dir = std::env::temp_dir().join(format!("spacepix-key-test-{}", std::process::id()));
+        let key_file = dir.join("secret.json");
+        std::fs::create_dir_all(&dir).unwrap();
+
+        std::fs::write(&key_file, r#"{"key":"DEMO_KEY"}"#).unwrap();
+        assert_eq!(Parser::from_key_file(&key_file).unwrap().get_api_key(), "DEMO_KEY");
+        std::fs::write(&key_file, r#"{"key":"#).unwrap();
+        assert!(matches!(Parser::from_key_file(&key_file), Err(ApiKeyError::MalformedKeyFile(_))));
+        std::fs::write(&key_file, r#"{"token":"DEMO_KEY"}"#).unwrap();
+        assert!(matches!(Parser::from_key_file(&key_file), Err(ApiKeyError::MissingKey())));
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert!(matches!(Parser::from_key_file(&key_file), Err(ApiKeyError::KeyFile(_))));
+    }
+
+    #[test]
+    fn test_is_key_well_formed() {
+        assert!(Parser::is_key_well_formed("DEMO_KEY"));
+        assert!(Parser::is_key_well_formed("abcdefghijABCDEFGHIJ0123456789abcdefghij"));
+        assert!(!Parser::is_key_well_formed("abcdefghijABCDEFGHIJ0123456789abcdefghi"));
+        assert!(!Parser::is_key_well_formed("abcdefghijABCDEFGHIJ 123456789abcdefghij"));
+    }
+
+    #[test]
+    fn test_key_profiles() {
+        let dir = std::env::temp_dir().join(format!("spacepix-profile-test-{}", std::process::id()));
+        let mut parser = Parser::from_profiles(KeyProfiles::with_machine_secret(dir.clone(), b"this machine"));
+        let key = "abcdefghijABCDEFGHIJ0123456789abcdefghij";
+
+        assert!(matches!(parser.switch_profile("Default"), Err(ApiKeyError::KeyFile(_))));
+        assert_eq!(parser.get_api_key(), "DEMO_KEY");
+        parser.set_api_key(String::from(key)).unwrap();
+        parser.add_profile("shared").unwrap();
+        assert!(matches!(parser.switch_profile("nobody"), Err(ApiKeyError::UnknownProfile(_))));
+        assert!(parser.switch_profile("shared").is_err());
+        assert_eq!((parser.profile(), parser.get_api_key().as_str()), ("shared", "DEMO_KEY"));
+        parser.switch_profile("Default").unwrap();
+        assert_eq!(parser.get_api_key(), key);
+
+        // Each profile counts its own requests, an override counts separately
+        let mut headers = reqwest::header::HeaderMap::new();
+        headers.insert("X-RateLimit-Remaining", "998".parse().unwrap());
+        parser.record_request(&headers);
+        parser.override_key(" DEMO_KEY ");
+        parser.record_request(&headers);
+        parser.record_request(&headers);
+        assert_eq!((parser.rate_limit("Default").requests, parser.rate_limit("Default").remaining), (1, Some(998)));
+        assert_eq!(parser.rate_limit(Parser::OVERRIDE_PROFILE).requests, 2);
+        assert_eq!(parser.rate_limit("shared").requests, 0);
+        assert_eq!(parser.get_api_key(), "DEMO_KEY");
+
+        parser.switch_profile("shared").ok();
+        parser.remove_profile("shared").unwrap();
+        assert_eq!((parser.profile(), parser.get_api_key().as_str()), ("Default", key));
+
+        // Nothing to switch back to once the default profile's key is forgotten
+        parser.forget_api_key().unwrap();
+        parser.add_profile("shared").unwrap();
+        parser.switch_profile("shared").ok();
+        parser.remove_profile("shared").unwrap();
+        assert_eq!((parser.profile(), parser.get_api_key().as_str()), ("Default", "DEMO_KEY"));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
 }
@@ -0,0 +1,147 @@
+use egui::{pos2, Align2, Color32, FontId, Pos2, Rect, Sense, Stroke, Vec2};
+
+/// A point on a LogScatter, x and y have to be positive to show up on log axes
+#[derive(Debug, Clone)]
+pub struct ScatterPoint {
+    pub x: f64,
+    pub y: f64,
+    pub color: Color32,
+    pub label: String,
+}
+
+impl ScatterPoint {
+    pub fn new(x: f64, y: f64, color: Color32, label: impl Into<String>) -> Self {
+        Self {
+            x,
+            y,
+            color,
+            label: label.into(),
+        }
+    }
+}
+
+/// Log-log scatter plot drawn with the egui painter, like the WorldMap
+pub struct LogScatter;
+
+impl LogScatter {
+    // How close the pointer has to be to a point to hover or click it
+    const PICK_RADIUS: f32 = 6.0;
+    // Room on the left and bottom for the tick labels and axis titles
+    const MARGIN: Vec2 = Vec2::new(52.0, 36.0);
+
+    // Draw the plot and return the index of the clicked point, if any
+    pub fn show(
+        ui: &mut egui::Ui,
+        size: Vec2,
+        x_label: &str,
+        y_label: &str,
+        points: &[ScatterPoint],
+    ) -> Option<usize> {
+        let (response, painter) = ui.allocate_painter(size, Sense::click());
+        let plot = Rect::from_min_max(
+            pos2(response.rect.left() + Self::MARGIN.x, response.rect.top() + 8.0),
+            pos2(response.rect.right() - 8.0, response.rect.bottom() - Self::MARGIN.y),
+        );
+        let plotted: Vec<&ScatterPoint> = points.iter().filter(|p| p.x > 0.0 && p.y > 0.0).collect();
+        let x_bounds = decade_bounds(plotted.iter().map(|p| p.x));
+        let y_bounds = decade_bounds(plotted.iter().map(|p| p.y));
+        let to_screen = |x: f64, y: f64| {
+            pos2(
+                plot.left() + log_position(x, x_bounds) * plot.width(),
+                plot.bottom() - log_position(y, y_bounds) * plot.height(),
+            )
+        };
+
+        let text = ui.visuals().text_color();
+        let font = FontId::proportional(11.0);
+        painter.rect_filled(plot, 0.0, ui.visuals().extreme_bg_color);
+        let grid = Stroke::new(0.5, ui.visuals().weak_text_color());
+        for exponent in x_bounds.0.log10() as i32..=x_bounds.1.log10() as i32 {
+            let x = to_screen(10f64.powi(exponent), y_bounds.0).x;
+            painter.line_segment([pos2(x, plot.top()), pos2(x, plot.bottom())], grid);
+            painter.text(pos2(x, plot.bottom() + 2.0), Align2::CENTER_TOP, decade_label(exponent), font.clone(), text);
+        }
+        for exponent in y_bounds.0.log10() as i32..=y_bounds.1.log10() as i32 {
+            let y = to_screen(x_bounds.0, 10f64.powi(exponent)).y;
+            painter.line_segment([pos2(plot.left(), y), pos2(plot.right(), y)], grid);
+            painter.text(pos2(plot.left() - 4.0, y), Align2::RIGHT_CENTER, decade_label(exponent), font.clone(), text);
+        }
+        painter.text(
+            pos2(plot.center().x, response.rect.bottom()),
+            Align2::CENTER_BOTTOM,
+            x_label,
+            font.clone(),
+            text,
+        );
+        painter.text(response.rect.left_top(), Align2::LEFT_TOP, y_label, font, text);
+
+        let positions: Vec<Pos2> = points.iter().map(|p| to_screen(p.x, p.y)).collect();
+        for (point, position) in points.iter().zip(&positions) {
+            if point.x > 0.0 && point.y > 0.0 {
+                painter.circle_filled(*position, 3.0, point.color);
+            }
+        }
+
+        let hovered = response.hover_pos().and_then(|pointer| {
+            positions
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| points[*i].x > 0.0 && points[*i].y > 0.0)
+                .map(|(i, position)| (i, position.distance(pointer)))
+                .filter(|(_, distance)| *distance <= Self::PICK_RADIUS)
+                .min_by(|a, b| a.1.total_cmp(&b.1))
+                .map(|(i, _)| i)
+        });
+        if let Some(i) = hovered {
+            response.clone().on_hover_text(&points[i].label);
+        }
+        match response.clicked() {
+            true => hovered,
+            false => None,
+        }
+    }
+}
+
+// The powers of ten just outside the smallest and largest positive values, 1..10 if there are none
+pub fn decade_bounds(values: impl Iterator<Item = f64>) -> (f64, f64) {
+    let (min, max) = values
+        .filter(|value| *value > 0.0 && value.is_finite())
+        .fold((f64::MAX, f64::MIN), |(min, max), value| (min.min(value), max.max(value)));
+    if min > max {
+        return (1.0, 10.0);
+    }
+    let low = min.log10().floor();
+    let high = max.log10().ceil().max(low + 1.0);
+    (10f64.powf(low), 10f64.powf(high))
+}
+
+// Where a value falls between the bounds on a log scale, 0.0 at the low end and 1.0 at the high end
+pub fn log_position(value: f64, bounds: (f64, f64)) -> f32 {
+    ((value.log10() - bounds.0.log10()) / (bounds.1.log10() - bounds.0.log10())) as f32
+}
+
+fn decade_label(exponent: i32) -> String {
+    match exponent {
+        -3..=5 => 10f64.powi(exponent).to_string(),
+        _ => format!("1e{}", exponent),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decade_bounds, log_position};
+
+    #[test]
+    fn test_decade_bounds() {
+        assert_eq!(decade_bounds([0.5, 3.0, 250.0].into_iter()), (0.1, 1000.0));
+        assert_eq!(decade_bounds([10.0].into_iter()), (10.0, 100.0));
+        assert_eq!(decade_bounds([0.0, -4.0].into_iter()), (1.0, 10.0));
+    }
+
+    #[test]
+    fn test_log_position() {
+        assert_eq!(log_position(0.1, (0.1, 1000.0)), 0.0);
+        assert_eq!(log_position(10.0, (0.1, 1000.0)), 0.5);
+        assert_eq!(log_position(1000.0, (0.1, 1000.0)), 1.0);
+    }
+}
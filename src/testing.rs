@@ -0,0 +1,38 @@
+// Helpers shared by the unit tests, a local HTTP server that answers with canned responses
+use std::io::{Read, Write};
+use std::net::TcpListener;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+// Answer each connection on a local port with the next canned response and count them,
+// None accepts the connection and then says nothing until the client gives up
+pub fn stub_server(responses: Vec<Option<String>>) -> (String, Arc<AtomicUsize>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/", listener.local_addr().unwrap());
+    let requests = Arc::new(AtomicUsize::new(0));
+    let counter = requests.clone();
+    std::thread::spawn(move || {
+        for response in responses {
+            let (mut stream, _) = listener.accept().unwrap();
+            let _ = stream.read(&mut [0u8; 4096]);
+            counter.fetch_add(1, Ordering::SeqCst);
+            std::thread::spawn(move || match response {
+                Some(response) => {
+                    let _ = stream.write_all(response.as_bytes());
+                }
+                None => std::thread::sleep(Duration::from_millis(1000)),
+            });
+        }
+    });
+    (url, requests)
+}
+
+pub fn http_response(status: &str, body: &str) -> String {
+    format!(
+        "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    )
+}
@@ -1,21 +1,57 @@
-use egui::Image;
+use crate::{
+    clear_request_log, configure_http_client, default_download_dir, redact_api_key, request_log,
+    save_file_blocking, Apod, CadQuery, CaptionCue, EarthAsset, EarthAssets, EarthLocation,
+    Eonet, EonetQuery, EpicCollection, EpicDay, EpicImage, EpicImageFormat, ExoplanetQuery,
+    ExoplanetTable, Exoplanets, Fireballs, HttpSettings, KeyProfiles, MarsPhotoDate,
+    MarsPhotoQuery, MarsRover, MarsRoverManifest, MarsRoverPhotos, NIVL, NIVLAsset, NIVLItem,
+    NIVLSearch, Parser, ProxySettings, RequestRecord, Sentry, SentryLookup, SentryObject,
+    SentryRecord, SpacepixError, TechTransfer, TechTransferCategory, Tle, TleSearch, WSAEnlil,
+    WSAEnlilSimulation,
+};
+use crate::map::{MapFeature, MapShape, WorldMap};
+use crate::plot::{LogScatter, ScatterPoint};
+use egui::{vec2, Color32, Image, RichText};
+use std::collections::{HashSet, VecDeque};
+use std::path::PathBuf;
 
 #[derive(Clone, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
 pub struct ApodWindow {
     pub apod_window_visible: bool,
     pub apod_full_window_visible: bool,
+    pub save_label: String,
+    #[serde(skip)]
+    pub error_label: String,
+    #[serde(skip)]
+    pub fetch_failed: bool, // Stops the window refetching every frame until Retry is clicked
 }
 
 impl Default for ApodWindow {
     fn default() -> Self {
         Self {
             apod_window_visible: false,
-            apod_full_window_visible: false
+            apod_full_window_visible: false,
+            save_label: String::default(),
+            error_label: String::default(),
+            fetch_failed: false,
         }
     }
 }
 
 impl ApodWindow {
+    // Save the full resolution APOD image to the download directory
+    pub fn save_apod(&mut self, apod: &Apod, errors: &mut ErrorPanel) {
+        let url = if apod.hdurl.is_empty() || apod.hdurl == "null" {
+            &apod.url
+        } else {
+            &apod.hdurl
+        };
+        self.save_label = match save_file_blocking(url, &default_download_dir()) {
+            Ok(path) => format!("Saved to {}", path.display()),
+            Err(e) => errors.report(ErrorSource::Download, &e),
+        };
+    }
+
     pub fn apod_full_window(
         &mut self,
         img: &Image,
@@ -51,36 +87,394 @@ impl ApodWindow {
     }
 }
 
+/// Where the NeoWs window gets its close approaches from
+#[derive(Clone, Copy, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub enum NeoSource {
+    #[default]
+    NeoWs,
+    Cad,
+}
+
 #[derive(Clone, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
 pub struct NeowsWindow {
     pub neows_date: String,
+    pub source: NeoSource,
+    pub cad: CadQuery,
     pub neows_invalid_input_window_visible: bool,
-    pub neows_window_visible: bool
+    pub neows_window_visible: bool,
+    #[serde(skip)]
+    pub error_label: String
 }
 
 impl Default for NeowsWindow {
     fn default() -> Self {
         Self {
             neows_date: String::default(),
+            source: NeoSource::default(),
+            cad: CadQuery::default(),
             neows_invalid_input_window_visible: false,
-            neows_window_visible: false
+            neows_window_visible: false,
+            error_label: String::default()
         }
     }
 }
 
-#[derive(Clone, serde::Serialize, serde::Deserialize)]
+impl NeowsWindow {
+    // Search form for the JPL CAD backend, returns true when Search is clicked
+    pub fn cad_search(&mut self, ui: &mut egui::Ui) -> bool {
+        ui.label("Dates as YYYY-MM-DD, \"now\" or days from now like \"+60\".");
+        egui::Grid::new("cad_search_grid")
+            .num_columns(2)
+            .show(ui, |ui| {
+                ui.label("From:");
+                ui.text_edit_singleline(&mut self.cad.date_min);
+                ui.end_row();
+                ui.label("To:");
+                ui.text_edit_singleline(&mut self.cad.date_max);
+                ui.end_row();
+                ui.label("Max Distance:")
+                    .on_hover_text("In au, or with a unit such as 1LD (lunar distances)");
+                ui.text_edit_singleline(&mut self.cad.dist_max);
+                ui.end_row();
+            });
+        ui.checkbox(&mut self.cad.include_comets, "Include comets");
+        ui.button("Search").clicked()
+    }
+}
+
+#[derive(Clone, Default, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
 pub struct NIVLWindow {
-    pub query: String,
-    pub nivl_window_visible: bool
+    pub search: NIVLSearch,
+    pub album_name: String,
+    pub favorite_albums: Vec<String>,
+    pub error_label: String,
+    pub download_label: String,
+    pub nivl_window_visible: bool,
+    #[serde(skip)]
+    pub failed_request: Option<NIVLSearch>, // Repeated by the error panel's Retry button
 }
 
-impl Default for NIVLWindow {
-    fn default() -> Self {
-        Self {
-            query: String::default(),
-            nivl_window_visible: false
+impl NIVLWindow {
+    const MEDIA_TYPES: [(&'static str, &'static str); 4] = [
+        ("", "All"),
+        ("image", "Images"),
+        ("video", "Videos"),
+        ("audio", "Audio"),
+    ];
+
+    pub fn nivl_window(&mut self, nivl: &mut Option<NIVL>, parser: &Parser, errors: &mut ErrorPanel, ctx: &egui::Context) {
+        let mut visible = self.nivl_window_visible;
+        egui::Window::new("NASA Image and Video Library")
+            .open(&mut visible)
+            .show(ctx, |ui| {
+                ui.label("Enter a search term");
+                ui.text_edit_singleline(&mut self.search.q);
+                egui::CollapsingHeader::new("Filters").show(ui, |ui| {
+                    egui::Grid::new("nivl_filter_grid")
+                        .num_columns(2)
+                        .show(ui, |ui| {
+                            ui.label("Media Type:");
+                            egui::ComboBox::from_id_salt("nivl_media_type")
+                                .selected_text(
+                                    Self::MEDIA_TYPES
+                                        .iter()
+                                        .find(|(value, _)| *value == self.search.media_type)
+                                        .map_or("All", |(_, label)| *label),
+                                )
+                                .show_ui(ui, |ui| {
+                                    for (value, label) in Self::MEDIA_TYPES {
+                                        ui.selectable_value(
+                                            &mut self.search.media_type,
+                                            value.to_string(),
+                                            label,
+                                        );
+                                    }
+                                });
+                            ui.end_row();
+                            ui.label("Year Start:");
+                            ui.text_edit_singleline(&mut self.search.year_start);
+                            ui.end_row();
+                            ui.label("Year End:");
+                            ui.text_edit_singleline(&mut self.search.year_end);
+                            ui.end_row();
+                            ui.label("Center:");
+                            ui.text_edit_singleline(&mut self.search.center);
+                            ui.end_row();
+                            ui.label("Keywords:");
+                            ui.text_edit_singleline(&mut self.search.keywords);
+                            ui.end_row();
+                        });
+                });
+
+                let mut request = None;
+                if ui.button("Search").clicked() {
+                    request = Some(NIVLSearch {
+                        page: 1,
+                        ..self.search.clone()
+                    });
+                }
+
+                egui::CollapsingHeader::new("Albums").show(ui, |ui| {
+                    ui.horizontal(|ui| {
+                        ui.label("Album:");
+                        ui.text_edit_singleline(&mut self.album_name);
+                    });
+                    let album_name = self.album_name.trim().to_string();
+                    ui.horizontal(|ui| {
+                        if ui
+                            .add_enabled(!album_name.is_empty(), egui::Button::new("Open Album"))
+                            .clicked()
+                        {
+                            request = Some(NIVLSearch::album(&album_name));
+                        }
+                        if ui
+                            .add_enabled(
+                                !album_name.is_empty() && !self.favorite_albums.contains(&album_name),
+                                egui::Button::new("Bookmark"),
+                            )
+                            .clicked()
+                        {
+                            self.favorite_albums.push(album_name.clone());
+                        }
+                    });
+
+                    let mut removed = None;
+                    for (i, favorite) in self.favorite_albums.iter().enumerate() {
+                        ui.horizontal(|ui| {
+                            if ui.link(favorite).clicked() {
+                                self.album_name = favorite.clone();
+                                request = Some(NIVLSearch::album(favorite));
+                            }
+                            if ui.small_button("Remove").clicked() {
+                                removed = Some(i);
+                            }
+                        });
+                    }
+                    if let Some(i) = removed {
+                        self.favorite_albums.remove(i);
+                    }
+                });
+
+                if let Some(results) = nivl {
+                    ui.horizontal(|ui| {
+                        let current = results.search.page;
+                        // Previous/Next keep paging the results on screen, not whatever is typed in the filters
+                        if ui
+                            .add_enabled(current > 1, egui::Button::new("Previous"))
+                            .clicked()
+                        {
+                            request = Some(NIVLSearch {
+                                page: current - 1,
+                                ..results.search.clone()
+                            });
+                        }
+                        if !results.search.album.is_empty() {
+                            ui.label(format!("Album: {}", results.search.album));
+                        }
+                        ui.label(format!(
+                            "Page {} of {} ({} results)",
+                            current,
+                            results.page_count(),
+                            results.total_hits
+                        ));
+                        if ui
+                            .add_enabled(current < results.page_count(), egui::Button::new("Next"))
+                            .clicked()
+                        {
+                            request = Some(NIVLSearch {
+                                page: current + 1,
+                                ..results.search.clone()
+                            });
+                        }
+                    });
+                }
+
+                if errors.take_retry(ErrorSource::Nivl) {
+                    request = self.failed_request.take().or(request);
+                }
+                if let Some(search) = request {
+                    match NIVL::search_blocking(parser, &search) {
+                        Ok(results) => {
+                            *nivl = Some(results);
+                            self.error_label = String::default();
+                        }
+                        Err(e) => {
+                            self.error_label = errors.report(ErrorSource::Nivl, &e);
+                            self.failed_request = Some(search);
+                        }
+                    }
+                }
+                ui.label(&self.error_label);
+                ui.separator();
+
+                if let Some(results) = nivl {
+                    let mut clicked = None;
+                    egui::ScrollArea::vertical().show(ui, |ui| {
+                        egui::Grid::new("nivl_thumbnail_grid")
+                            .spacing([10.0, 10.0])
+                            .show(ui, |ui| {
+                                for (i, item) in results.items.iter().enumerate() {
+                                    if Self::thumbnail(ui, item).clicked() {
+                                        clicked = Some(item.clone());
+                                    }
+                                    if (i + 1) % 4 == 0 {
+                                        ui.end_row();
+                                    }
+                                }
+                            });
+                    });
+
+                    if let Some(item) = clicked {
+                        match NIVLAsset::get_asset_blocking(parser, &item) {
+                            Ok(asset) => {
+                                results.selected = Some(asset);
+                                self.download_label = String::default();
+                            }
+                            Err(e) => self.error_label = errors.report(ErrorSource::NivlAsset, &e),
+                        }
+                    }
+                }
+            });
+        self.nivl_window_visible = visible;
+
+        if let Some(results) = nivl {
+            if let Some(asset) = &mut results.selected {
+                if !self.asset_window(asset, errors, ctx) {
+                    results.selected = None;
+                }
+            }
         }
     }
+
+    // Returns false once the user closes the window
+    fn asset_window(&mut self, asset: &mut NIVLAsset, errors: &mut ErrorPanel, ctx: &egui::Context) -> bool {
+        let mut open = true;
+        egui::Window::new(&asset.item.title)
+            .id(egui::Id::new("nivl_asset_window"))
+            .open(&mut open)
+            .show(ctx, |ui| {
+                let preview = asset
+                    .rendition("medium")
+                    .or(asset.rendition("small"))
+                    .map(|rendition| rendition.href.clone())
+                    .or(asset.item.thumbnail.clone());
+                if let Some(preview) = preview {
+                    ui.add(egui::Image::from_uri(preview).max_size(vec2(500.0, 500.0)));
+                }
+                egui::Grid::new("nivl_asset_grid")
+                    .num_columns(2)
+                    .show(ui, |ui| {
+                        ui.strong("NASA Id:");
+                        ui.label(&asset.item.nasa_id);
+                        ui.end_row();
+                        ui.strong("Date Created:");
+                        ui.label(&asset.item.date_created);
+                        ui.end_row();
+                        ui.strong("Photographer:");
+                        ui.label(&asset.item.photographer);
+                        ui.end_row();
+                        ui.strong("Center:");
+                        ui.label(&asset.item.center);
+                        ui.end_row();
+                        ui.strong("Keywords:");
+                        ui.label(asset.item.keywords.join(", "));
+                        ui.end_row();
+                        if let Some(duration) = &asset.duration {
+                            ui.strong("Duration:");
+                            ui.label(duration);
+                            ui.end_row();
+                        }
+                    });
+                if let Some(media) = asset.playable() {
+                    if ui.button(format!("Play {}", asset.item.media_type)).clicked() {
+                        if let Err(e) = open::that(&media.href) {
+                            self.download_label = e.to_string();
+                        }
+                    }
+                }
+                ui.separator();
+                egui::ScrollArea::vertical()
+                    .max_height(200.0)
+                    .show(ui, |ui| {
+                        ui.label(&asset.item.description);
+                    });
+                ui.separator();
+
+                ui.heading("Downloads");
+                for rendition in &asset.renditions {
+                    ui.horizontal(|ui| {
+                        ui.label(&rendition.name);
+                        if ui.button("Download").clicked() {
+                            self.download_label =
+                                match save_file_blocking(&rendition.href, &default_download_dir()) {
+                                    Ok(path) => format!("Saved to {}", path.display()),
+                                    Err(e) => errors.report(ErrorSource::Download, &e),
+                                };
+                        }
+                    });
+                }
+                ui.label(&self.download_label);
+
+                let mut load_captions = None;
+                for caption_file in asset.caption_files() {
+                    if ui
+                        .button(format!("View Captions ({})", caption_file.extension()))
+                        .clicked()
+                    {
+                        load_captions = Some(caption_file.clone());
+                    }
+                }
+                if let Some(caption_file) = load_captions {
+                    match CaptionCue::get_captions_blocking(&caption_file) {
+                        Ok(captions) => asset.captions = Some(captions),
+                        Err(e) => self.download_label = errors.report(ErrorSource::Captions, &e),
+                    }
+                }
+                if let Some(captions) = &asset.captions {
+                    ui.separator();
+                    egui::ScrollArea::vertical()
+                        .id_salt("nivl_captions")
+                        .max_height(200.0)
+                        .show(ui, |ui| {
+                            egui::Grid::new("nivl_captions_grid")
+                                .num_columns(2)
+                                .striped(true)
+                                .show(ui, |ui| {
+                                    for cue in captions {
+                                        ui.monospace(format!("{} - {}", cue.start, cue.end));
+                                        ui.label(&cue.text);
+                                        ui.end_row();
+                                    }
+                                });
+                        });
+                }
+            });
+        open
+    }
+
+    fn thumbnail(ui: &mut egui::Ui, item: &NIVLItem) -> egui::Response {
+        ui.vertical(|ui| {
+            ui.set_width(150.0);
+            let response = match &item.thumbnail {
+                Some(thumbnail) => ui.add(egui::ImageButton::new(
+                    egui::Image::from_uri(thumbnail).fit_to_exact_size(vec2(150.0, 150.0)),
+                )),
+                None => ui.add_sized(
+                    [150.0, 150.0],
+                    egui::Button::new(item.media_type.to_uppercase()),
+                ),
+            };
+            let title = match item.media_type.as_str() {
+                "image" => item.title.clone(),
+                media_type => format!("[{}] {}", media_type, item.title),
+            };
+            ui.add(egui::Label::new(title).truncate());
+            response.on_hover_text(&item.title)
+        })
+        .inner
+    }
 }
 
 #[derive(Clone, serde::Serialize, serde::Deserialize)]
@@ -99,7 +493,12 @@ impl Default for AboutWindow {
 #[derive(Clone, serde::Serialize, serde::Deserialize)]
 pub struct ApiKeyWindow {
     pub api_key_window_visible: bool,
+    #[serde(skip)]
     pub key: String,
+    #[serde(skip)]
+    pub passphrase: String,
+    #[serde(skip)]
+    pub locked: bool, // The saved key needs its passphrase before Spacepix can use it
     pub key_set_label: String
 }
 
@@ -108,7 +507,2238 @@ impl Default for ApiKeyWindow {
         Self {
             api_key_window_visible: false,
             key: String::default(),
+            passphrase: String::default(),
+            locked: false,
             key_set_label: String::default()
         }
     }
 }
+
+#[derive(Clone, Default, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct WSAEnlilWindow {
+    pub start_date: String,
+    pub end_date: String,
+    pub cme_id: String,
+    pub error_label: String,
+    pub wsa_enlil_window_visible: bool,
+}
+
+impl WSAEnlilWindow {
+    pub fn wsa_enlil_window(
+        &mut self,
+        enlil: &mut Option<WSAEnlil>,
+        parser: &Parser,
+        errors: &mut ErrorPanel,
+        ctx: &egui::Context,
+    ) {
+        let mut visible = self.wsa_enlil_window_visible;
+        egui::Window::new("DONKI - WSA-Enlil Simulations")
+            .open(&mut visible)
+            .show(ctx, |ui| {
+                ui.label("Date format: YYYY-MM-DD");
+                egui::Grid::new("wsa_enlil_date_grid")
+                    .num_columns(2)
+                    .show(ui, |ui| {
+                        ui.label("Start Date:");
+                        ui.text_edit_singleline(&mut self.start_date);
+                        ui.end_row();
+                        ui.label("End Date:");
+                        ui.text_edit_singleline(&mut self.end_date);
+                        ui.end_row();
+                    });
+                if ui.button("Search").clicked() || errors.take_retry(ErrorSource::WsaEnlil) {
+                    match WSAEnlil::get_simulations_blocking(parser, &self.start_date, &self.end_date) {
+                        Ok(simulations) => {
+                            *enlil = Some(simulations);
+                            self.error_label = String::default();
+                        }
+                        Err(e) => self.error_label = errors.report(ErrorSource::WsaEnlil, &e),
+                    }
+                }
+                ui.label(&self.error_label);
+
+                // Cross reference a CME from the DONKI views with the runs that modeled it
+                ui.horizontal(|ui| {
+                    ui.label("CME Id:");
+                    ui.text_edit_singleline(&mut self.cme_id);
+                });
+                ui.separator();
+
+                if let Some(enlil) = enlil {
+                    let simulations = match self.cme_id.trim() {
+                        "" => enlil.simulations.iter().collect(),
+                        cme_id => enlil.simulations_for_cme(cme_id),
+                    };
+                    if simulations.is_empty() {
+                        ui.label("No simulations found.");
+                    }
+                    egui::ScrollArea::vertical().show(ui, |ui| {
+                        for simulation in simulations {
+                            self.simulation_entry(ui, simulation);
+                        }
+                    });
+                }
+            });
+        self.wsa_enlil_window_visible = visible;
+    }
+
+    fn simulation_entry(&mut self, ui: &mut egui::Ui, simulation: &WSAEnlilSimulation) {
+        if ui
+            .link(RichText::new(&simulation.simulation_id).size(20.0).strong())
+            .clicked()
+        {
+            let _ = open::that(&simulation.link);
+        }
+        ui.label(format!("Model Completed: {}", simulation.model_completion_time));
+        for input in &simulation.cme_inputs {
+            ui.horizontal(|ui| {
+                ui.label("CME:");
+                if ui.link(&input.cme_id).clicked() {
+                    self.cme_id = input.cme_id.clone();
+                }
+                ui.label(format!("{} km/s", input.speed));
+            });
+        }
+        egui::Grid::new(&simulation.simulation_id)
+            .num_columns(3)
+            .striped(true)
+            .show(ui, |ui| {
+                ui.strong("Target");
+                ui.strong("Estimated Shock Arrival");
+                ui.strong("Glancing Blow");
+                ui.end_row();
+                for impact in simulation.targets() {
+                    ui.label(&impact.location);
+                    ui.label(&impact.arrival_time);
+                    ui.label(if impact.is_glancing_blow { "Yes" } else { "No" });
+                    ui.end_row();
+                }
+            });
+        if let (Some(kp_90), Some(kp_180)) = (simulation.kp.1, simulation.kp.3) {
+            ui.label(format!("Predicted Kp: {} - {}", kp_90, kp_180));
+        }
+        ui.separator();
+    }
+}
+
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct MarsRoverWindow {
+    pub rover: MarsRover,
+    pub use_earth_date: bool,
+    pub sol: u32,
+    pub earth_date: String,
+    pub camera: Option<String>,
+    pub error_label: String,
+    pub mars_rover_window_visible: bool,
+    #[serde(skip)]
+    pub failed_query: Option<MarsPhotoQuery>, // Repeated by the error panel's Retry button
+}
+
+impl Default for MarsRoverWindow {
+    fn default() -> Self {
+        Self {
+            rover: MarsRover::default(),
+            use_earth_date: false,
+            sol: 1000,
+            earth_date: String::default(),
+            camera: None,
+            error_label: String::default(),
+            mars_rover_window_visible: false,
+            failed_query: None,
+        }
+    }
+}
+
+impl MarsRoverWindow {
+    pub fn mars_rover_window(
+        &mut self,
+        photos: &mut Option<MarsRoverPhotos>,
+        manifest: &mut Option<MarsRoverManifest>,
+        parser: &Parser,
+        errors: &mut ErrorPanel,
+        ctx: &egui::Context,
+    ) {
+        let mut visible = self.mars_rover_window_visible;
+        egui::Window::new("Mars Rover Photos")
+            .open(&mut visible)
+            .show(ctx, |ui| {
+                let previous_rover = self.rover;
+                egui::ComboBox::from_label("Rover")
+                    .selected_text(self.rover.to_string())
+                    .show_ui(ui, |ui| {
+                        for rover in MarsRover::ALL {
+                            ui.selectable_value(&mut self.rover, rover, rover.to_string());
+                        }
+                    });
+                let stale = manifest.as_ref().is_none_or(|m| m.rover != self.rover);
+                if previous_rover != self.rover {
+                    self.camera = None;
+                }
+                let retry_manifest = errors.take_retry(ErrorSource::MarsManifest);
+                if stale && (previous_rover != self.rover || ui.button("Load Mission Manifest").clicked() || retry_manifest) {
+                    match MarsRoverManifest::get_manifest_blocking(parser, self.rover) {
+                        Ok(m) => {
+                            self.sol = self.sol.min(m.max_sol);
+                            *manifest = Some(m);
+                            self.error_label = String::default();
+                        }
+                        Err(e) => self.error_label = errors.report(ErrorSource::MarsManifest, &e),
+                    }
+                }
+
+                let manifest = manifest.as_ref().filter(|m| m.rover == self.rover);
+                if let Some(manifest) = manifest {
+                    ui.label(format!(
+                        "Status: {}  Landed: {}  Last Sol: {} ({})  Photos: {}",
+                        manifest.status,
+                        manifest.landing_date,
+                        manifest.max_sol,
+                        manifest.max_date,
+                        manifest.total_photos
+                    ));
+                }
+
+                ui.horizontal(|ui| {
+                    ui.radio_value(&mut self.use_earth_date, false, "Sol");
+                    ui.radio_value(&mut self.use_earth_date, true, "Earth Date");
+                });
+                let mut cameras: Vec<String> = self.rover.cameras().iter().map(|c| c.to_string()).collect();
+                if self.use_earth_date {
+                    ui.label("Date format: YYYY-MM-DD");
+                    ui.text_edit_singleline(&mut self.earth_date);
+                } else {
+                    let max_sol = manifest.map_or(5000, |m| m.max_sol);
+                    ui.add(egui::Slider::new(&mut self.sol, 0..=max_sol).text("Sol"));
+                    if let Some(manifest) = manifest {
+                        match manifest.sol(self.sol) {
+                            Some(sol) => {
+                                ui.label(format!("{} photos taken on {}", sol.total_photos, sol.earth_date));
+                                cameras = sol.cameras.clone();
+                            }
+                            None => {
+                                ui.label("No photos were taken on this sol.");
+                            }
+                        }
+                    }
+                }
+
+                egui::ComboBox::from_label("Camera")
+                    .selected_text(self.camera.clone().unwrap_or(String::from("All")))
+                    .show_ui(ui, |ui| {
+                        ui.selectable_value(&mut self.camera, None, "All");
+                        for camera in cameras {
+                            ui.selectable_value(&mut self.camera, Some(camera.clone()), camera);
+                        }
+                    });
+
+                let mut query = None;
+                if ui.button("Search").clicked() {
+                    query = Some(MarsPhotoQuery {
+                        rover: self.rover,
+                        date: match self.use_earth_date {
+                            true => MarsPhotoDate::EarthDate(self.earth_date.clone()),
+                            false => MarsPhotoDate::Sol(self.sol),
+                        },
+                        camera: self.camera.clone(),
+                        page: 1,
+                    });
+                }
+                if let Some(results) = photos.as_ref() {
+                    ui.horizontal(|ui| {
+                        let current = results.query.page;
+                        if ui.add_enabled(current > 1, egui::Button::new("Previous")).clicked() {
+                            query = Some(MarsPhotoQuery {
+                                page: current - 1,
+                                ..results.query.clone()
+                            });
+                        }
+                        ui.label(format!("Page {}", current));
+                        if ui
+                            .add_enabled(results.has_next_page(), egui::Button::new("Next"))
+                            .clicked()
+                        {
+                            query = Some(MarsPhotoQuery {
+                                page: current + 1,
+                                ..results.query.clone()
+                            });
+                        }
+                    });
+                }
+                if errors.take_retry(ErrorSource::MarsPhotos) {
+                    query = self.failed_query.take().or(query);
+                }
+                if let Some(query) = query {
+                    match MarsRoverPhotos::get_photos_blocking(parser, &query) {
+                        Ok(results) => {
+                            *photos = Some(results);
+                            self.error_label = String::default();
+                        }
+                        Err(e) => {
+                            self.error_label = errors.report(ErrorSource::MarsPhotos, &e);
+                            self.failed_query = Some(query);
+                        }
+                    }
+                }
+                ui.label(&self.error_label);
+                ui.separator();
+
+                if let Some(results) = photos {
+                    if results.photos.is_empty() {
+                        ui.label("No photos found.");
+                    }
+                    egui::ScrollArea::vertical().show(ui, |ui| {
+                        egui::Grid::new("mars_photo_grid")
+                            .spacing([10.0, 10.0])
+                            .show(ui, |ui| {
+                                for (i, photo) in results.photos.iter().enumerate() {
+                                    ui.vertical(|ui| {
+                                        if ui
+                                            .add(egui::ImageButton::new(
+                                                egui::Image::from_uri(&photo.img_src)
+                                                    .fit_to_exact_size(vec2(150.0, 150.0)),
+                                            ))
+                                            .on_hover_text(&photo.camera_full_name)
+                                            .clicked()
+                                        {
+                                            let _ = open::that(&photo.img_src);
+                                        }
+                                        ui.label(format!("{} - Sol {}", photo.camera, photo.sol));
+                                    });
+                                    if (i + 1) % 4 == 0 {
+                                        ui.end_row();
+                                    }
+                                }
+                            });
+                    });
+                }
+            });
+        self.mars_rover_window_visible = visible;
+    }
+}
+
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct EpicWindow {
+    pub collection: EpicCollection,
+    pub date: String,
+    pub frame: usize,
+    pub playing: bool,
+    pub frame_delay: f64, // Seconds each image stays on screen while animating
+    #[serde(skip)]
+    pub last_frame_time: f64,
+    #[serde(skip)]
+    pub images: KeyedImages,
+    pub error_label: String,
+    pub epic_window_visible: bool,
+}
+
+impl Default for EpicWindow {
+    fn default() -> Self {
+        Self {
+            collection: EpicCollection::default(),
+            date: String::default(),
+            frame: 0,
+            playing: false,
+            frame_delay: 0.5,
+            last_frame_time: 0.0,
+            images: KeyedImages::default(),
+            error_label: String::default(),
+            epic_window_visible: false,
+        }
+    }
+}
+
+impl EpicWindow {
+    pub fn epic_window(&mut self, epic: &mut Option<EpicDay>, parser: &Parser, errors: &mut ErrorPanel, ctx: &egui::Context) {
+        let mut visible = self.epic_window_visible;
+        egui::Window::new("EPIC - Earth Polychromatic Imaging Camera")
+            .open(&mut visible)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.radio_value(&mut self.collection, EpicCollection::Natural, "Natural Color");
+                    ui.radio_value(&mut self.collection, EpicCollection::Enhanced, "Enhanced Color");
+                });
+                ui.label("Date format: YYYY-MM-DD (leave empty for the latest images)");
+                ui.text_edit_singleline(&mut self.date);
+                if ui.button("Search").clicked() || errors.take_retry(ErrorSource::Epic) {
+                    match EpicDay::get_day_blocking(parser, self.collection, &self.date) {
+                        Ok(day) => {
+                            *epic = Some(day);
+                            self.frame = 0;
+                            self.images.clear_failed();
+                            self.error_label = String::default();
+                        }
+                        Err(e) => self.error_label = errors.report(ErrorSource::Epic, &e),
+                    }
+                }
+                ui.label(&self.error_label);
+                ui.separator();
+
+                let Some(day) = epic else {
+                    return;
+                };
+                if day.images.is_empty() {
+                    ui.label("No images found for this date.");
+                    return;
+                }
+                let frame_count = day.images.len();
+                self.frame = self.frame.min(frame_count - 1);
+
+                ui.horizontal(|ui| {
+                    if ui.button(if self.playing { "Pause" } else { "Play" }).clicked() {
+                        self.playing = !self.playing;
+                    }
+                    // Fetch the whole day before playing, the animation would stall on each new image
+                    // otherwise. One failure is enough to stop and say so
+                    if self.playing {
+                        for image in &day.images {
+                            let uri = parser.epic_image_uri(day.collection, image, EpicImageFormat::Jpg);
+                            let fetched = self.images.fetch(ctx, &uri, || {
+                                image.get_image_blocking(parser, day.collection, EpicImageFormat::Jpg)
+                            });
+                            if let Err(e) = fetched {
+                                self.playing = false;
+                                self.error_label = errors.report(ErrorSource::Epic, &e);
+                                break;
+                            }
+                        }
+                    }
+                    ui.add(egui::Slider::new(&mut self.frame, 0..=frame_count - 1).text("Image"));
+                    ui.add(
+                        egui::Slider::new(&mut self.frame_delay, 0.1..=2.0)
+                            .text("Seconds per image"),
+                    );
+                });
+                if self.playing {
+                    // Step through the day's images to spin the Earth
+                    let now = ui.input(|i| i.time);
+                    if now - self.last_frame_time >= self.frame_delay {
+                        self.frame = (self.frame + 1) % frame_count;
+                        self.last_frame_time = now;
+                    }
+                    ctx.request_repaint_after(std::time::Duration::from_secs_f64(self.frame_delay));
+                }
+
+                let image = &day.images[self.frame];
+                let uri = parser.epic_image_uri(day.collection, image, EpicImageFormat::Jpg);
+                let fetched = self.images.fetch(ctx, &uri, || {
+                    image.get_image_blocking(parser, day.collection, EpicImageFormat::Jpg)
+                });
+                if let Err(e) = fetched {
+                    self.error_label = errors.report(ErrorSource::Epic, &e);
+                }
+                self.images.show(ui, &uri, vec2(400.0, 400.0));
+                ui.label(&image.date);
+                egui::Grid::new("epic_metadata_grid")
+                    .num_columns(2)
+                    .striped(true)
+                    .show(ui, |ui| {
+                        ui.strong("Centroid:");
+                        ui.label(format!("{:.2}° lat, {:.2}° lon", image.centroid.0, image.centroid.1));
+                        ui.end_row();
+                        for (name, position) in [
+                            ("DSCOVR:", image.dscovr_position),
+                            ("Sun:", image.sun_position),
+                            ("Moon:", image.lunar_position),
+                        ] {
+                            ui.strong(name);
+                            ui.label(format!(
+                                "x {:.0} km, y {:.0} km, z {:.0} km ({:.0} km from Earth)",
+                                position.0,
+                                position.1,
+                                position.2,
+                                EpicImage::distance(position)
+                            ));
+                            ui.end_row();
+                        }
+                    });
+                ui.label(&image.caption);
+            });
+        self.epic_window_visible = visible;
+    }
+}
+
+#[derive(Clone, Default, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct EonetWindow {
+    pub query: EonetQuery,
+    pub selected_event: Option<String>,
+    pub error_label: String,
+    pub eonet_window_visible: bool,
+}
+
+impl EonetWindow {
+    const STATUSES: [&'static str; 3] = ["open", "closed", "all"];
+
+    fn category_color(category: &str) -> Color32 {
+        match category {
+            "wildfires" => Color32::from_rgb(255, 80, 40),
+            "volcanoes" => Color32::from_rgb(255, 170, 0),
+            "severeStorms" => Color32::from_rgb(80, 220, 255),
+            "seaLakeIce" | "snow" => Color32::WHITE,
+            "earthquakes" => Color32::YELLOW,
+            "floods" => Color32::from_rgb(60, 120, 255),
+            "landslides" => Color32::from_rgb(160, 110, 60),
+            "dustHaze" | "drought" => Color32::from_rgb(210, 180, 120),
+            "tempExtremes" => Color32::from_rgb(255, 0, 160),
+            _ => Color32::LIGHT_GRAY,
+        }
+    }
+
+    pub fn eonet_window(&mut self, eonet: &mut Option<Eonet>, parser: &Parser, errors: &mut ErrorPanel, ctx: &egui::Context) {
+        let mut visible = self.eonet_window_visible;
+        egui::Window::new("EONET - Natural Events")
+            .open(&mut visible)
+            .show(ctx, |ui| {
+                let eonet = eonet.get_or_insert_with(Eonet::default);
+                let retry_filters = errors.take_retry(ErrorSource::EonetFilters);
+                if eonet.categories.is_empty() && (ui.button("Load Categories and Sources").clicked() || retry_filters) {
+                    if let Err(e) = eonet.get_filters_blocking(parser) {
+                        self.error_label = errors.report(ErrorSource::EonetFilters, &e);
+                    }
+                }
+
+                egui::Grid::new("eonet_filter_grid")
+                    .num_columns(2)
+                    .show(ui, |ui| {
+                        ui.label("Status:");
+                        ui.horizontal(|ui| {
+                            for status in Self::STATUSES {
+                                ui.radio_value(&mut self.query.status, status.to_string(), status);
+                            }
+                        });
+                        ui.end_row();
+
+                        ui.label("Category:");
+                        let selected = eonet
+                            .categories
+                            .iter()
+                            .find(|category| Some(&category.id) == self.query.category.as_ref())
+                            .map_or(String::from("All"), |category| category.title.clone());
+                        egui::ComboBox::from_id_salt("eonet_category")
+                            .selected_text(selected)
+                            .show_ui(ui, |ui| {
+                                ui.selectable_value(&mut self.query.category, None, "All");
+                                for category in &eonet.categories {
+                                    ui.selectable_value(
+                                        &mut self.query.category,
+                                        Some(category.id.clone()),
+                                        &category.title,
+                                    );
+                                }
+                            });
+                        ui.end_row();
+
+                        ui.label("Source:");
+                        let selected = eonet
+                            .sources
+                            .iter()
+                            .find(|source| Some(&source.id) == self.query.source.as_ref())
+                            .map_or(String::from("All"), |source| source.title.clone());
+                        egui::ComboBox::from_id_salt("eonet_source")
+                            .selected_text(selected)
+                            .show_ui(ui, |ui| {
+                                ui.selectable_value(&mut self.query.source, None, "All");
+                                for source in &eonet.sources {
+                                    ui.selectable_value(
+                                        &mut self.query.source,
+                                        Some(source.id.clone()),
+                                        &source.title,
+                                    );
+                                }
+                            });
+                        ui.end_row();
+
+                        ui.label("Days:");
+                        ui.add(egui::Slider::new(&mut self.query.days, 1..=365));
+                        ui.end_row();
+                    });
+
+                if ui.button("Search").clicked() || errors.take_retry(ErrorSource::Eonet) {
+                    match eonet.get_events_blocking(parser, &self.query) {
+                        Ok(_) => self.error_label = String::default(),
+                        Err(e) => self.error_label = errors.report(ErrorSource::Eonet, &e),
+                    }
+                }
+                ui.label(&self.error_label);
+                ui.separator();
+
+                // One map feature per geometry, remembering which event it belongs to
+                let mut feature_events = Vec::default();
+                let mut features = Vec::default();
+                for event in &eonet.events {
+                    let selected = self.selected_event.as_ref() == Some(&event.id);
+                    let color = match selected {
+                        true => Color32::from_rgb(0, 255, 0),
+                        false => Self::category_color(
+                            event.categories.first().map_or("", |category| category.id.as_str()),
+                        ),
+                    };
+                    // Storms report a point per observation, connect them into a track
+                    let track: Vec<(f64, f64)> = event
+                        .geometry
+                        .iter()
+                        .filter(|geometry| geometry.coordinates.len() == 1)
+                        .map(|geometry| geometry.coordinates[0])
+                        .collect();
+                    if track.len() > 1 {
+                        features.push(MapFeature::new(MapShape::Track(track), color, &event.title));
+                        feature_events.push(event.id.clone());
+                    }
+                    for geometry in &event.geometry {
+                        let shape = match geometry.coordinates.as_slice() {
+                            [] => continue,
+                            [(lon, lat)] => MapShape::Point(*lon, *lat),
+                            points => MapShape::Polygon(points.to_vec()),
+                        };
+                        features.push(MapFeature::new(
+                            shape,
+                            color,
+                            format!("{} ({})", event.title, geometry.date),
+                        ));
+                        feature_events.push(event.id.clone());
+                    }
+                }
+                if let Some(i) = WorldMap::show(ui, 600.0, &features) {
+                    self.selected_event = Some(feature_events[i].clone());
+                }
+                ui.label(format!("{} events", eonet.events.len()));
+                ui.separator();
+
+                egui::ScrollArea::vertical().show(ui, |ui| {
+                    for event in &eonet.events {
+                        let selected = self.selected_event.as_ref() == Some(&event.id);
+                        ui.horizontal(|ui| {
+                            if ui.selectable_label(selected, &event.title).clicked() {
+                                self.selected_event = Some(event.id.clone());
+                            }
+                            if ui.link("Details").clicked() {
+                                let _ = open::that(&event.link);
+                            }
+                        });
+                        if selected {
+                            let categories: Vec<&str> = event
+                                .categories
+                                .iter()
+                                .map(|category| category.title.as_str())
+                                .collect();
+                            ui.label(format!("Category: {}", categories.join(", ")));
+                            ui.label(match &event.closed {
+                                Some(closed) => format!("Closed: {}", closed),
+                                None => String::from("Open"),
+                            });
+                            if let Some(latest) = event.geometry.last() {
+                                ui.label(format!("Last observed: {}", latest.date));
+                                if let Some((value, unit)) = &latest.magnitude {
+                                    ui.label(format!("Magnitude: {} {}", value, unit));
+                                }
+                            }
+                            if !event.description.is_empty() {
+                                ui.label(&event.description);
+                            }
+                            for source in &event.sources {
+                                if ui.link(format!("Source: {}", source.id)).clicked() {
+                                    let _ = open::that(&source.url);
+                                }
+                            }
+                        }
+                    }
+                });
+            });
+        self.eonet_window_visible = visible;
+    }
+}
+
+#[derive(Clone, Copy, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub enum SentrySort {
+    #[default]
+    Palermo,
+    ImpactProbability,
+    Torino,
+}
+
+#[derive(Clone, Default, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct SentryWindow {
+    pub sort: SentrySort,
+    pub filter: String,
+    pub error_label: String,
+    pub sentry_window_visible: bool,
+}
+
+impl SentryWindow {
+    // Open a single Sentry record, e.g. from a NeoWs entry flagged as a Sentry object
+    pub fn open_record(&mut self, sentry: &mut Option<Sentry>, parser: &Parser, errors: &mut ErrorPanel, lookup: &SentryLookup) {
+        match SentryRecord::get_record_blocking(parser, lookup) {
+            Ok(record) => {
+                sentry.get_or_insert_with(Sentry::default).selected = Some(record);
+                self.error_label = String::default();
+            }
+            Err(e) => self.error_label = errors.report(ErrorSource::SentryRecord, &e),
+        }
+        self.sentry_window_visible = true;
+    }
+
+    pub fn sentry_window(&mut self, sentry: &mut Option<Sentry>, parser: &Parser, errors: &mut ErrorPanel, ctx: &egui::Context) {
+        let mut visible = self.sentry_window_visible;
+        egui::Window::new("CNEOS Sentry - Impact Risk")
+            .open(&mut visible)
+            .show(ctx, |ui| {
+                let sentry = sentry.get_or_insert_with(Sentry::default);
+                ui.horizontal(|ui| {
+                    if ui.button("Load Risk List").clicked() || errors.take_retry(ErrorSource::Sentry) {
+                        match sentry.get_objects_blocking(parser) {
+                            Ok(_) => self.error_label = String::default(),
+                            Err(e) => self.error_label = errors.report(ErrorSource::Sentry, &e),
+                        }
+                    }
+                    ui.label("Filter:");
+                    ui.text_edit_singleline(&mut self.filter);
+                });
+                ui.horizontal(|ui| {
+                    ui.label("Sort by:");
+                    ui.radio_value(&mut self.sort, SentrySort::Palermo, "Palermo Scale");
+                    ui.radio_value(&mut self.sort, SentrySort::ImpactProbability, "Impact Probability");
+                    ui.radio_value(&mut self.sort, SentrySort::Torino, "Torino Scale");
+                });
+                ui.label(&self.error_label);
+                ui.separator();
+
+                let filter = self.filter.trim().to_lowercase();
+                let mut objects: Vec<&SentryObject> = sentry
+                    .objects
+                    .iter()
+                    .filter(|object| object.fullname.to_lowercase().contains(&filter))
+                    .collect();
+                match self.sort {
+                    SentrySort::Palermo => {
+                        objects.sort_by(|a, b| b.palermo_scale.0.total_cmp(&a.palermo_scale.0))
+                    }
+                    SentrySort::ImpactProbability => {
+                        objects.sort_by(|a, b| b.impact_probability.total_cmp(&a.impact_probability))
+                    }
+                    SentrySort::Torino => {
+                        objects.sort_by_key(|object| std::cmp::Reverse(object.torino_scale))
+                    }
+                }
+
+                let mut clicked = None;
+                egui::ScrollArea::vertical().show(ui, |ui| {
+                    egui::Grid::new("sentry_grid")
+                        .num_columns(6)
+                        .striped(true)
+                        .show(ui, |ui| {
+                            ui.strong("Object");
+                            ui.strong("Impact Probability");
+                            ui.strong("Palermo (cum / max)");
+                            ui.strong("Torino");
+                            ui.strong("Years");
+                            ui.strong("Potential Impacts");
+                            ui.end_row();
+                            for object in objects {
+                                if ui.link(&object.fullname).clicked() {
+                                    clicked = Some(object.des.clone());
+                                }
+                                ui.label(format!("{:.2e}", object.impact_probability));
+                                ui.label(format!(
+                                    "{:.2} / {:.2}",
+                                    object.palermo_scale.0, object.palermo_scale.1
+                                ));
+                                ui.label(object.torino_scale.map_or(String::from("-"), |ts| ts.to_string()));
+                                ui.label(&object.range);
+                                ui.label(object.n_imp.to_string());
+                                ui.end_row();
+                            }
+                        });
+                });
+                if let Some(des) = clicked {
+                    match SentryRecord::get_record_blocking(parser, &SentryLookup::Des(des)) {
+                        Ok(record) => sentry.selected = Some(record),
+                        Err(e) => self.error_label = errors.report(ErrorSource::SentryRecord, &e),
+                    }
+                }
+            });
+        self.sentry_window_visible = visible;
+
+        if let Some(sentry) = sentry {
+            if let Some(record) = &sentry.selected {
+                if !Self::record_window(record, ctx) {
+                    sentry.selected = None;
+                }
+            }
+        }
+    }
+
+    // Returns false once the user closes the window
+    fn record_window(record: &SentryRecord, ctx: &egui::Context) -> bool {
+        let mut open = true;
+        egui::Window::new(&record.object.fullname)
+            .id(egui::Id::new("sentry_record_window"))
+            .open(&mut open)
+            .show(ctx, |ui| {
+                ui.label(format!(
+                    "Cumulative impact probability: {:.2e}",
+                    record.object.impact_probability
+                ));
+                ui.label(format!(
+                    "Palermo Scale: {:.2} cumulative, {:.2} max",
+                    record.object.palermo_scale.0, record.object.palermo_scale.1
+                ));
+                if let Some(diameter) = record.object.diameter {
+                    ui.label(format!("Estimated diameter: {} km", diameter));
+                }
+                ui.label(format!("Last observed: {}", record.object.last_obs));
+                if ui.link("View on CNEOS").clicked() {
+                    let _ = open::that(format!(
+                        "https://cneos.jpl.nasa.gov/sentry/details.html#?des={}",
+                        record.object.des
+                    ));
+                }
+                ui.separator();
+                egui::ScrollArea::vertical().show(ui, |ui| {
+                    egui::Grid::new("sentry_impact_grid")
+                        .num_columns(5)
+                        .striped(true)
+                        .show(ui, |ui| {
+                            ui.strong("Date");
+                            ui.strong("Impact Probability");
+                            ui.strong("Palermo");
+                            ui.strong("Torino");
+                            ui.strong("Energy (Mt)");
+                            ui.end_row();
+                            for impact in &record.impacts {
+                                ui.label(&impact.date);
+                                ui.label(format!("{:.2e}", impact.impact_probability));
+                                ui.label(format!("{:.2}", impact.palermo_scale));
+                                ui.label(impact.torino_scale.map_or(String::from("-"), |ts| ts.to_string()));
+                                ui.label(impact.energy.map_or(String::from("-"), |energy| format!("{:.2e}", energy)));
+                                ui.end_row();
+                            }
+                        });
+                });
+            });
+        open
+    }
+}
+
+#[derive(Clone, Copy, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub enum FireballSort {
+    #[default]
+    Date,
+    Energy,
+    ImpactEnergy,
+}
+
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct FireballWindow {
+    pub limit: u32,
+    pub date_min: String,
+    pub sort: FireballSort,
+    pub selected: Option<usize>,
+    pub error_label: String,
+    pub fireball_window_visible: bool,
+}
+
+impl Default for FireballWindow {
+    fn default() -> Self {
+        Self {
+            limit: 50,
+            date_min: String::default(),
+            sort: FireballSort::default(),
+            selected: None,
+            error_label: String::default(),
+            fireball_window_visible: false,
+        }
+    }
+}
+
+impl FireballWindow {
+    pub fn fireball_window(&mut self, fireballs: &mut Option<Fireballs>, parser: &Parser, errors: &mut ErrorPanel, ctx: &egui::Context) {
+        let mut visible = self.fireball_window_visible;
+        egui::Window::new("Fireballs - Bolide Reports")
+            .open(&mut visible)
+            .show(ctx, |ui| {
+                egui::Grid::new("fireball_filter_grid")
+                    .num_columns(2)
+                    .show(ui, |ui| {
+                        ui.label("Since (YYYY-MM-DD):");
+                        ui.text_edit_singleline(&mut self.date_min);
+                        ui.end_row();
+                        ui.label("Events:");
+                        ui.add(egui::Slider::new(&mut self.limit, 1..=500));
+                        ui.end_row();
+                    });
+                if ui.button("Search").clicked() || errors.take_retry(ErrorSource::Fireballs) {
+                    match Fireballs::get_fireballs_blocking(parser, self.limit, &self.date_min) {
+                        Ok(results) => {
+                            *fireballs = Some(results);
+                            self.selected = None;
+                            self.error_label = String::default();
+                        }
+                        Err(e) => self.error_label = errors.report(ErrorSource::Fireballs, &e),
+                    }
+                }
+                ui.label(&self.error_label);
+
+                let Some(fireballs) = fireballs else {
+                    return;
+                };
+                ui.horizontal(|ui| {
+                    ui.label("Sort by:");
+                    ui.radio_value(&mut self.sort, FireballSort::Date, "Date");
+                    ui.radio_value(&mut self.sort, FireballSort::Energy, "Radiated Energy");
+                    ui.radio_value(&mut self.sort, FireballSort::ImpactEnergy, "Impact Energy");
+                });
+                ui.separator();
+
+                // Indices into fireballs.events, so the map and the table agree on what is selected
+                let mut order: Vec<usize> = (0..fireballs.events.len()).collect();
+                match self.sort {
+                    FireballSort::Date => {}
+                    FireballSort::Energy => order.sort_by(|a, b| {
+                        fireballs.events[*b].energy.total_cmp(&fireballs.events[*a].energy)
+                    }),
+                    FireballSort::ImpactEnergy => order.sort_by(|a, b| {
+                        fireballs.events[*b]
+                            .impact_energy
+                            .total_cmp(&fireballs.events[*a].impact_energy)
+                    }),
+                }
+
+                // Not every report comes with a location
+                let located: Vec<usize> = order
+                    .iter()
+                    .copied()
+                    .filter(|i| fireballs.events[*i].lat.is_some() && fireballs.events[*i].lon.is_some())
+                    .collect();
+                let features: Vec<MapFeature> = located
+                    .iter()
+                    .map(|i| {
+                        let fireball = &fireballs.events[*i];
+                        let color = match self.selected == Some(*i) {
+                            true => Color32::from_rgb(0, 255, 0),
+                            false => Color32::from_rgb(255, 140, 0),
+                        };
+                        MapFeature::new(
+                            MapShape::Point(fireball.lon.unwrap_or_default(), fireball.lat.unwrap_or_default()),
+                            color,
+                            format!("{} ({} kt)", fireball.date, fireball.impact_energy),
+                        )
+                    })
+                    .collect();
+                if let Some(i) = WorldMap::show(ui, 600.0, &features) {
+                    self.selected = Some(located[i]);
+                }
+                ui.separator();
+
+                let optional = |value: Option<f64>| value.map_or(String::from("-"), |value| value.to_string());
+                egui::ScrollArea::vertical().show(ui, |ui| {
+                    egui::Grid::new("fireball_grid")
+                        .num_columns(7)
+                        .striped(true)
+                        .show(ui, |ui| {
+                            ui.strong("Date (UTC)");
+                            ui.strong("Radiated Energy (10^10 J)");
+                            ui.strong("Impact Energy (kt)");
+                            ui.strong("Lat");
+                            ui.strong("Lon");
+                            ui.strong("Altitude (km)");
+                            ui.strong("Velocity (km/s)");
+                            ui.end_row();
+                            for i in order {
+                                let fireball = &fireballs.events[i];
+                                if ui.selectable_label(self.selected == Some(i), &fireball.date).clicked() {
+                                    self.selected = Some(i);
+                                }
+                                ui.label(fireball.energy.to_string());
+                                ui.label(fireball.impact_energy.to_string());
+                                ui.label(optional(fireball.lat));
+                                ui.label(optional(fireball.lon));
+                                ui.label(optional(fireball.altitude));
+                                ui.label(optional(fireball.velocity));
+                                ui.end_row();
+                            }
+                        });
+                });
+            });
+        self.fireball_window_visible = visible;
+    }
+}
+
+#[derive(Clone, Default, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct ExoplanetWindow {
+    pub query: ExoplanetQuery,
+    pub selected: Option<usize>,
+    pub error_label: String,
+    pub exoplanet_window_visible: bool,
+}
+
+impl ExoplanetWindow {
+    pub fn exoplanet_window(&mut self, exoplanets: &mut Option<Exoplanets>, parser: &Parser, errors: &mut ErrorPanel, ctx: &egui::Context) {
+        let mut visible = self.exoplanet_window_visible;
+        egui::Window::new("Exoplanet Archive")
+            .open(&mut visible)
+            .show(ctx, |ui| {
+                egui::Grid::new("exoplanet_filter_grid")
+                    .num_columns(3)
+                    .show(ui, |ui| {
+                        ui.label("Table:");
+                        egui::ComboBox::from_id_salt("exoplanet_table")
+                            .selected_text(self.query.table.to_string())
+                            .show_ui(ui, |ui| {
+                                for table in ExoplanetTable::ALL {
+                                    ui.selectable_value(&mut self.query.table, table, table.to_string());
+                                }
+                            });
+                        ui.end_row();
+                        ui.label("Discovery Method:");
+                        egui::ComboBox::from_id_salt("exoplanet_discovery_method")
+                            .selected_text(match self.query.discovery_method.is_empty() {
+                                true => "Any",
+                                false => &self.query.discovery_method,
+                            })
+                            .show_ui(ui, |ui| {
+                                ui.selectable_value(&mut self.query.discovery_method, String::default(), "Any");
+                                for method in ExoplanetQuery::DISCOVERY_METHODS {
+                                    ui.selectable_value(&mut self.query.discovery_method, method.to_string(), method);
+                                }
+                            });
+                        ui.end_row();
+                        ui.label("");
+                        ui.label("Min");
+                        ui.label("Max");
+                        ui.end_row();
+                        for (label, min, max) in [
+                            ("Discovery Year:", &mut self.query.year_min, &mut self.query.year_max),
+                            ("Radius (Earth radii):", &mut self.query.radius_min, &mut self.query.radius_max),
+                            ("Mass (Earth masses):", &mut self.query.mass_min, &mut self.query.mass_max),
+                            ("Orbital Period (days):", &mut self.query.period_min, &mut self.query.period_max),
+                        ] {
+                            ui.label(label);
+                            ui.add(egui::TextEdit::singleline(min).desired_width(80.0));
+                            ui.add(egui::TextEdit::singleline(max).desired_width(80.0));
+                            ui.end_row();
+                        }
+                        ui.label("Rows:");
+                        ui.add(egui::Slider::new(&mut self.query.limit, 1..=5000).logarithmic(true));
+                        ui.end_row();
+                    });
+                ui.collapsing("ADQL", |ui| {
+                    ui.monospace(self.query.adql());
+                });
+                if ui.button("Search").clicked() || errors.take_retry(ErrorSource::Exoplanets) {
+                    match Exoplanets::get_exoplanets_blocking(parser, &self.query) {
+                        Ok(results) => {
+                            *exoplanets = Some(results);
+                            self.selected = None;
+                            self.error_label = String::default();
+                        }
+                        Err(e) => self.error_label = errors.report(ErrorSource::Exoplanets, &e),
+                    }
+                }
+                ui.label(&self.error_label);
+
+                let Some(exoplanets) = exoplanets else {
+                    return;
+                };
+                ui.label(format!("{} planets", exoplanets.planets.len()));
+                ui.separator();
+
+                // Planets without a period or radius are left off the plot but stay in the table
+                let points: Vec<ScatterPoint> = exoplanets
+                    .planets
+                    .iter()
+                    .enumerate()
+                    .map(|(i, planet)| {
+                        let color = match self.selected == Some(i) {
+                            true => Color32::from_rgb(0, 255, 0),
+                            false => Color32::from_rgb(100, 170, 255),
+                        };
+                        ScatterPoint::new(
+                            planet.period.unwrap_or_default(),
+                            planet.radius.unwrap_or_default(),
+                            color,
+                            &planet.name,
+                        )
+                    })
+                    .collect();
+                if let Some(i) = LogScatter::show(
+                    ui,
+                    vec2(600.0, 300.0),
+                    "Orbital Period (days)",
+                    "Radius (Earth radii)",
+                    &points,
+                ) {
+                    self.selected = Some(i);
+                }
+                ui.separator();
+
+                let optional = |value: Option<f64>| value.map_or(String::from("-"), |value| value.to_string());
+                egui::ScrollArea::vertical().show(ui, |ui| {
+                    egui::Grid::new("exoplanet_grid")
+                        .num_columns(7)
+                        .striped(true)
+                        .show(ui, |ui| {
+                            ui.strong("Planet");
+                            ui.strong("Host");
+                            ui.strong("Discovery Method");
+                            ui.strong("Year");
+                            ui.strong("Radius (R⊕)");
+                            ui.strong("Mass (M⊕)");
+                            ui.strong("Period (days)");
+                            ui.end_row();
+                            for (i, planet) in exoplanets.planets.iter().enumerate() {
+                                if ui.selectable_label(self.selected == Some(i), &planet.name).clicked() {
+                                    self.selected = Some(i);
+                                }
+                                ui.label(&planet.host);
+                                ui.label(&planet.discovery_method);
+                                ui.label(planet.discovery_year.map_or(String::from("-"), |year| year.to_string()));
+                                ui.label(optional(planet.radius));
+                                ui.label(optional(planet.mass));
+                                ui.label(optional(planet.period));
+                                ui.end_row();
+                            }
+                        });
+                });
+            });
+        self.exoplanet_window_visible = visible;
+    }
+}
+
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct LandsatWindow {
+    pub location: EarthLocation,
+    pub saved_locations: Vec<EarthLocation>,
+    pub begin: String,
+    pub end: String,
+    pub samples: u32, // How many dates between begin and end to ask for the nearest acquisition
+    pub frame: usize,
+    pub compare: bool,
+    pub compare_frame: usize,
+    #[serde(skip)]
+    pub images: KeyedImages,
+    pub error_label: String,
+    pub landsat_window_visible: bool,
+}
+
+impl Default for LandsatWindow {
+    fn default() -> Self {
+        Self {
+            location: EarthLocation::default(),
+            saved_locations: Vec::default(),
+            begin: String::from("2019-01-01"),
+            end: String::from("2020-12-31"),
+            samples: 8,
+            frame: 0,
+            compare: false,
+            compare_frame: 0,
+            images: KeyedImages::default(),
+            error_label: String::default(),
+            landsat_window_visible: false,
+        }
+    }
+}
+
+impl LandsatWindow {
+    pub fn landsat_window(&mut self, landsat: &mut Option<EarthAssets>, parser: &Parser, errors: &mut ErrorPanel, ctx: &egui::Context) {
+        let mut visible = self.landsat_window_visible;
+        egui::Window::new("Landsat - Earth Imagery")
+            .open(&mut visible)
+            .show(ctx, |ui| {
+                egui::Grid::new("landsat_location_grid")
+                    .num_columns(2)
+                    .show(ui, |ui| {
+                        ui.label("Name:");
+                        ui.text_edit_singleline(&mut self.location.name);
+                        ui.end_row();
+                        ui.label("Latitude:");
+                        ui.add(egui::DragValue::new(&mut self.location.lat).range(-90.0..=90.0).speed(0.01));
+                        ui.end_row();
+                        ui.label("Longitude:");
+                        ui.add(egui::DragValue::new(&mut self.location.lon).range(-180.0..=180.0).speed(0.01));
+                        ui.end_row();
+                        ui.label("Width (degrees):");
+                        ui.add(egui::Slider::new(&mut self.location.dim, 0.025..=0.5));
+                        ui.end_row();
+                        ui.label("From (YYYY-MM-DD):");
+                        ui.text_edit_singleline(&mut self.begin);
+                        ui.end_row();
+                        ui.label("To (YYYY-MM-DD):");
+                        ui.text_edit_singleline(&mut self.end);
+                        ui.end_row();
+                        ui.label("Dates to check:");
+                        ui.add(egui::Slider::new(&mut self.samples, 1..=24));
+                        ui.end_row();
+                    });
+
+                let mut search = false;
+                ui.horizontal(|ui| {
+                    search = ui.button("Search").clicked() || errors.take_retry(ErrorSource::Landsat);
+                    let name = self.location.name.trim().to_string();
+                    if ui
+                        .add_enabled(
+                            !name.is_empty() && !self.saved_locations.iter().any(|saved| saved.name == name),
+                            egui::Button::new("Save Location"),
+                        )
+                        .clicked()
+                    {
+                        self.saved_locations.push(EarthLocation {
+                            name,
+                            ..self.location.clone()
+                        });
+                    }
+                });
+
+                egui::CollapsingHeader::new("Saved Locations").show(ui, |ui| {
+                    let features: Vec<MapFeature> = self
+                        .saved_locations
+                        .iter()
+                        .map(|saved| {
+                            MapFeature::new(
+                                MapShape::Point(saved.lon, saved.lat),
+                                Color32::from_rgb(255, 200, 0),
+                                &saved.name,
+                            )
+                        })
+                        .collect();
+                    if let Some(i) = WorldMap::show(ui, 400.0, &features) {
+                        self.location = self.saved_locations[i].clone();
+                        search = true;
+                    }
+
+                    let mut removed = None;
+                    for (i, saved) in self.saved_locations.iter().enumerate() {
+                        ui.horizontal(|ui| {
+                            if ui.link(&saved.name).clicked() {
+                                self.location = saved.clone();
+                                search = true;
+                            }
+                            ui.label(format!("{:.2}, {:.2}", saved.lat, saved.lon));
+                            if ui.small_button("Remove").clicked() {
+                                removed = Some(i);
+                            }
+                        });
+                    }
+                    if let Some(i) = removed {
+                        self.saved_locations.remove(i);
+                    }
+                });
+
+                if search {
+                    match EarthAssets::sample_dates(&self.begin, &self.end, self.samples) {
+                        Some(dates) => match EarthAssets::get_assets_blocking(parser, &self.location, &dates) {
+                            Ok(assets) => {
+                                // Start out comparing the oldest and newest acquisitions
+                                self.frame = assets.assets.len().saturating_sub(1);
+                                self.compare_frame = 0;
+                                *landsat = Some(assets);
+                                self.images.clear_failed();
+                                self.error_label = String::default();
+                            }
+                            Err(e) => self.error_label = errors.report(ErrorSource::Landsat, &e),
+                        },
+                        None => self.error_label = String::from("Dates must be in the format YYYY-MM-DD"),
+                    }
+                }
+                ui.label(&self.error_label);
+                ui.separator();
+
+                let Some(landsat) = landsat else {
+                    return;
+                };
+                if landsat.assets.is_empty() {
+                    ui.label("No imagery found for this location.");
+                    return;
+                }
+                let last = landsat.assets.len() - 1;
+                self.frame = self.frame.min(last);
+                self.compare_frame = self.compare_frame.min(last);
+
+                ui.label(format!(
+                    "{} acquisitions of {} ({:.2}, {:.2})",
+                    landsat.assets.len(),
+                    landsat.location.name,
+                    landsat.location.lat,
+                    landsat.location.lon
+                ));
+                ui.horizontal_wrapped(|ui| {
+                    for (i, asset) in landsat.assets.iter().enumerate() {
+                        if ui.selectable_label(self.frame == i, asset.day()).clicked() {
+                            self.frame = i;
+                        }
+                    }
+                });
+                ui.checkbox(&mut self.compare, "Compare two dates");
+
+                let mut image = |ui: &mut egui::Ui, asset: &EarthAsset| {
+                    let uri = parser.earth_imagery_uri(&landsat.location, asset.day());
+                    let fetched = self.images.fetch(ctx, &uri, || asset.get_image_blocking(parser, &landsat.location));
+                    if let Err(e) = fetched {
+                        self.error_label = errors.report(ErrorSource::Landsat, &e);
+                    }
+                    ui.vertical(|ui| {
+                        self.images.show(ui, &uri, vec2(400.0, 400.0));
+                        ui.label(&asset.date);
+                    });
+                };
+                if self.compare {
+                    ui.add(egui::Slider::new(&mut self.compare_frame, 0..=last).text("Before"));
+                    ui.add(egui::Slider::new(&mut self.frame, 0..=last).text("After"));
+                    ui.horizontal(|ui| {
+                        image(ui, &landsat.assets[self.compare_frame]);
+                        image(ui, &landsat.assets[self.frame]);
+                    });
+                } else {
+                    ui.add(egui::Slider::new(&mut self.frame, 0..=last).text("Acquisition"));
+                    image(ui, &landsat.assets[self.frame]);
+                }
+            });
+        self.landsat_window_visible = visible;
+    }
+}
+
+#[derive(Clone, Default, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct TechTransferWindow {
+    pub query: String,
+    pub category: TechTransferCategory,
+    pub error_label: String,
+    pub techtransfer_window_visible: bool,
+}
+
+impl TechTransferWindow {
+    pub fn techtransfer_window(&mut self, techtransfer: &mut Option<TechTransfer>, parser: &Parser, errors: &mut ErrorPanel, ctx: &egui::Context) {
+        let mut visible = self.techtransfer_window_visible;
+        egui::Window::new("TechTransfer - Patents, Software and Spinoffs")
+            .open(&mut visible)
+            .show(ctx, |ui| {
+                let mut search = false;
+                ui.horizontal(|ui| {
+                    for category in TechTransferCategory::ALL {
+                        // Switching tabs runs the same search against the other category
+                        if ui
+                            .selectable_value(&mut self.category, category, category.to_string())
+                            .clicked()
+                        {
+                            search = !self.query.trim().is_empty();
+                        }
+                    }
+                });
+                ui.separator();
+                ui.horizontal(|ui| {
+                    ui.label("Search:");
+                    let response = ui.text_edit_singleline(&mut self.query);
+                    let entered = response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+                    if ui.button("Search").clicked() || entered || errors.take_retry(ErrorSource::TechTransfer) {
+                        search = true;
+                    }
+                });
+                if search {
+                    match TechTransfer::search_blocking(parser, self.category, &self.query) {
+                        Ok(results) => {
+                            *techtransfer = Some(results);
+                            self.error_label = String::default();
+                        }
+                        Err(e) => self.error_label = errors.report(ErrorSource::TechTransfer, &e),
+                    }
+                }
+                ui.label(&self.error_label);
+
+                let Some(techtransfer) = techtransfer else {
+                    return;
+                };
+                ui.label(format!(
+                    "Showing {} of {} {} for \"{}\"",
+                    techtransfer.results.len(),
+                    techtransfer.total,
+                    techtransfer.category.to_string().to_lowercase(),
+                    techtransfer.query
+                ));
+                ui.separator();
+
+                egui::ScrollArea::vertical().show(ui, |ui| {
+                    for result in &techtransfer.results {
+                        ui.horizontal(|ui| {
+                            if let Some(image) = &result.image {
+                                ui.add(Image::from_uri(image).fit_to_exact_size(vec2(96.0, 96.0)));
+                            }
+                            ui.vertical(|ui| {
+                                if ui.link(RichText::new(&result.title).strong()).clicked() {
+                                    let _ = open::that(&result.url);
+                                }
+                                ui.label(format!("{} - {}", result.case_number, result.category));
+                                ui.label(&result.description);
+                            });
+                        });
+                        ui.separator();
+                    }
+                });
+            });
+        self.techtransfer_window_visible = visible;
+    }
+}
+
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct TleWindow {
+    pub search: String,
+    pub pinned: Vec<Tle>,
+    pub selected: Option<Tle>,
+    pub orbits_ahead: u32, // How many orbits of ground track to draw past the current position
+    #[serde(skip)]
+    pub refresh_pinned: bool, // Pinned element sets go stale, so fetch them again once per session
+    pub error_label: String,
+    pub tle_window_visible: bool,
+    #[serde(skip)]
+    pub failed_request: Option<(String, u32)>, // Search and page repeated by the error panel's Retry button
+}
+
+impl Default for TleWindow {
+    fn default() -> Self {
+        Self {
+            search: String::default(),
+            pinned: vec![Tle {
+                satellite_id: Tle::ISS,
+                name: String::from("ISS (ZARYA)"),
+                ..Default::default()
+            }],
+            selected: None,
+            orbits_ahead: 1,
+            refresh_pinned: true,
+            error_label: String::default(),
+            tle_window_visible: false,
+            failed_request: None,
+        }
+    }
+}
+
+impl TleWindow {
+    pub fn tle_window(&mut self, tle_search: &mut Option<TleSearch>, parser: &Parser, errors: &mut ErrorPanel, ctx: &egui::Context) {
+        let mut visible = self.tle_window_visible;
+        egui::Window::new("Satellites - TLE Ground Tracks")
+            .open(&mut visible)
+            .show(ctx, |ui| {
+                if self.refresh_pinned || errors.take_retry(ErrorSource::TlePinned) {
+                    self.refresh_pinned = false;
+                    for pinned in self.pinned.iter_mut() {
+                        match Tle::get_tle_blocking(parser, pinned.satellite_id) {
+                            Ok(tle) => *pinned = tle,
+                            Err(e) => {
+                                self.error_label = errors.report(ErrorSource::TlePinned, &e);
+                                // Keep the old element sets rather than wait out a timeout for each
+                                if e.is_unreachable() {
+                                    break;
+                                }
+                            }
+                        }
+                    }
+                }
+
+                let mut request = None;
+                ui.horizontal(|ui| {
+                    ui.label("Search:");
+                    ui.text_edit_singleline(&mut self.search);
+                    if ui.button("Search").clicked() {
+                        request = Some((self.search.clone(), 1));
+                    }
+                });
+                if let Some(results) = tle_search {
+                    ui.horizontal(|ui| {
+                        let current = results.page;
+                        if ui.add_enabled(current > 1, egui::Button::new("Previous")).clicked() {
+                            request = Some((results.search.clone(), current - 1));
+                        }
+                        ui.label(format!("Page {} of {} ({} satellites)", current, results.page_count(), results.total_items));
+                        if ui
+                            .add_enabled(current < results.page_count(), egui::Button::new("Next"))
+                            .clicked()
+                        {
+                            request = Some((results.search.clone(), current + 1));
+                        }
+                    });
+                }
+                if errors.take_retry(ErrorSource::Tle) {
+                    request = self.failed_request.take().or(request);
+                }
+                if let Some((search, page)) = request {
+                    match TleSearch::search_blocking(parser, &search, page) {
+                        Ok(results) => {
+                            *tle_search = Some(results);
+                            self.error_label = String::default();
+                        }
+                        Err(e) => {
+                            self.error_label = errors.report(ErrorSource::Tle, &e);
+                            self.failed_request = Some((search, page));
+                        }
+                    }
+                }
+                ui.label(&self.error_label);
+
+                ui.columns(2, |columns| {
+                    columns[0].strong("Search Results");
+                    egui::ScrollArea::vertical()
+                        .id_salt("tle_results")
+                        .max_height(150.0)
+                        .show(&mut columns[0], |ui| {
+                            for tle in tle_search.iter().flat_map(|results| &results.satellites) {
+                                ui.horizontal(|ui| {
+                                    let selected = self.selected.as_ref().is_some_and(|s| s.satellite_id == tle.satellite_id);
+                                    if ui.selectable_label(selected, format!("{} ({})", tle.name, tle.satellite_id)).clicked() {
+                                        self.selected = Some(tle.clone());
+                                    }
+                                    if !self.pinned.iter().any(|p| p.satellite_id == tle.satellite_id)
+                                        && ui.small_button("Pin").clicked()
+                                    {
+                                        self.pinned.push(tle.clone());
+                                    }
+                                });
+                            }
+                        });
+
+                    columns[1].strong("Pinned");
+                    let mut unpinned = None;
+                    for (i, tle) in self.pinned.iter().enumerate() {
+                        columns[1].horizontal(|ui| {
+                            let selected = self.selected.as_ref().is_some_and(|s| s.satellite_id == tle.satellite_id);
+                            if ui.selectable_label(selected, &tle.name).clicked() {
+                                self.selected = Some(tle.clone());
+                            }
+                            if ui.small_button("Unpin").clicked() {
+                                unpinned = Some(i);
+                            }
+                        });
+                    }
+                    if let Some(i) = unpinned {
+                        self.pinned.remove(i);
+                    }
+                    if columns[1].button("Refresh Pinned").clicked() {
+                        self.refresh_pinned = true;
+                    }
+                });
+                ui.add(egui::Slider::new(&mut self.orbits_ahead, 1..=5).text("Orbits ahead"));
+                ui.separator();
+
+                // Propagate from the clock every frame so the satellites move across the map
+                let now = chrono::Utc::now().naive_utc();
+                let mut shown: Vec<(&Tle, Color32)> = self
+                    .pinned
+                    .iter()
+                    .map(|tle| (tle, Color32::from_rgb(255, 200, 0)))
+                    .collect();
+                if let Some(selected) = &self.selected {
+                    shown.retain(|(tle, _)| tle.satellite_id != selected.satellite_id);
+                    shown.push((selected, Color32::from_rgb(0, 255, 0)));
+                }
+                let mut features: Vec<MapFeature> = Vec::default();
+                let mut clickable: Vec<Option<&Tle>> = Vec::default();
+                for (tle, color) in &shown {
+                    let Ok(orbit) = tle.orbit() else {
+                        continue;
+                    };
+                    let period = orbit.period_minutes() as i64;
+                    let track = orbit.ground_track(
+                        now - chrono::Duration::minutes(period / 2),
+                        now + chrono::Duration::minutes(period * self.orbits_ahead as i64),
+                        1,
+                    );
+                    features.push(MapFeature::new(
+                        MapShape::Track(track.iter().map(|position| (position.lon, position.lat)).collect()),
+                        *color,
+                        &tle.name,
+                    ));
+                    clickable.push(None);
+                    if let Ok(position) = orbit.ground_position(now) {
+                        features.push(MapFeature::new(
+                            MapShape::Point(position.lon, position.lat),
+                            *color,
+                            format!("{} - {:.0} km", tle.name, position.altitude),
+                        ));
+                        clickable.push(Some(tle));
+                    }
+                }
+                if let Some(Some(tle)) = WorldMap::show(ui, 600.0, &features).map(|i| clickable[i]) {
+                    self.selected = Some(tle.clone());
+                }
+                ctx.request_repaint_after(std::time::Duration::from_secs(1));
+
+                let Some(selected) = &self.selected else {
+                    return;
+                };
+                match selected.orbit().and_then(|orbit| Ok((orbit.ground_position(now)?, orbit))) {
+                    Ok((position, orbit)) => {
+                        egui::Grid::new("tle_position_grid")
+                            .num_columns(2)
+                            .striped(true)
+                            .show(ui, |ui| {
+                                ui.strong("Satellite:");
+                                ui.label(format!("{} ({})", selected.name, selected.satellite_id));
+                                ui.end_row();
+                                ui.strong("Position:");
+                                ui.label(format!("{:.2}° lat, {:.2}° lon", position.lat, position.lon));
+                                ui.end_row();
+                                ui.strong("Altitude:");
+                                ui.label(format!("{:.0} km", position.altitude));
+                                ui.end_row();
+                                ui.strong("Period:");
+                                ui.label(format!("{:.1} minutes", orbit.period_minutes()));
+                                ui.end_row();
+                                ui.strong("Epoch:");
+                                ui.label(format!("{} UTC", orbit.epoch().format("%Y-%m-%d %H:%M:%S")));
+                                ui.end_row();
+                            });
+                    }
+                    Err(e) => {
+                        ui.label(e.to_string());
+                    }
+                }
+                ui.monospace(format!("{}\n{}", selected.line1, selected.line2));
+            });
+        self.tle_window_visible = visible;
+    }
+}
+
+#[derive(Clone, Copy, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub enum WelcomeStep {
+    #[default]
+    Intro,
+    EnterKey,
+    Done,
+}
+
+/// First run wizard, shown when there is no usable API key file
+#[derive(Clone, Default, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct WelcomeWindow {
+    pub step: WelcomeStep,
+    #[serde(skip)]
+    pub key: String,
+    pub key_error: String, // Why the saved key file couldn't be used, empty on a real first run
+    pub status_label: String,
+    pub welcome_window_visible: bool,
+}
+
+impl WelcomeWindow {
+    pub fn welcome_window(&mut self, parser: &mut Parser, ctx: &egui::Context) {
+        let mut visible = self.welcome_window_visible;
+        egui::Window::new("Welcome to Spacepix")
+            .open(&mut visible)
+            .collapsible(false)
+            .anchor(egui::Align2::CENTER_CENTER, vec2(0.0, 0.0))
+            .show(ctx, |ui| {
+                if !self.key_error.is_empty() {
+                    ui.colored_label(Color32::from_rgb(255, 140, 0), format!("Your saved API key couldn't be read: {}", self.key_error));
+                    ui.separator();
+                }
+                match self.step {
+                    WelcomeStep::Intro => {
+                        ui.label("Spacepix explores NASA's open APIs, which need an API key.");
+                        ui.label("Until you enter your own, Spacepix uses NASA's shared DEMO_KEY, which is limited to:");
+                        ui.label("  • 30 requests per hour");
+                        ui.label("  • 50 requests per day");
+                        ui.label("Personal keys are free and allow 1,000 requests per hour.");
+                        if ui.link("Sign up for a key at api.nasa.gov").clicked() {
+                            let _ = open::that("https://api.nasa.gov/");
+                        }
+                        ui.separator();
+                        ui.horizontal(|ui| {
+                            if ui.button("Enter My Key").clicked() {
+                                self.step = WelcomeStep::EnterKey;
+                            }
+                            if ui.button("Use DEMO_KEY For Now").clicked() {
+                                self.save_key(parser, Parser::DEMO_KEY);
+                            }
+                        });
+                    }
+                    WelcomeStep::EnterKey => {
+                        ui.label("Paste the key from your api.nasa.gov sign up email:");
+                        ui.text_edit_singleline(&mut self.key);
+                        let key = self.key.trim().to_string();
+                        let well_formed = Parser::is_key_well_formed(&key);
+                        if !key.is_empty() && !well_formed {
+                            ui.colored_label(Color32::from_rgb(255, 80, 80), "NASA API keys are 40 letters and numbers.");
+                        }
+                        ui.horizontal(|ui| {
+                            if ui.button("Back").clicked() {
+                                self.step = WelcomeStep::Intro;
+                            }
+                            if ui.add_enabled(well_formed, egui::Button::new("Save Key")).clicked() {
+                                self.save_key(parser, &key);
+                            }
+                        });
+                        ui.label(&self.status_label);
+                    }
+                    WelcomeStep::Done => {
+                        ui.label(&self.status_label);
+                        ui.label("You can change your key at any time from Settings->Set API Key.");
+                        if ui.button("Start Exploring").clicked() {
+                            self.welcome_window_visible = false;
+                        }
+                    }
+                }
+            });
+        self.welcome_window_visible = visible && self.welcome_window_visible;
+    }
+
+    // Save the key so the wizard doesn't come back, even when sticking with DEMO_KEY
+    fn save_key(&mut self, parser: &mut Parser, key: &str) {
+        match parser.change_api_key_blocking(key) {
+            Ok(_) => {
+                self.key_error = String::default();
+                self.status_label = match key == Parser::DEMO_KEY {
+                    true => String::from("Using DEMO_KEY."),
+                    false => String::from("NASA accepted your key, Spacepix is using it now."),
+                };
+                self.step = WelcomeStep::Done;
+            }
+            Err(e) => self.status_label = e.to_string(),
+        }
+    }
+}
+
+/// Settings->Key Profiles, named API keys that each get their own rate limit counters
+#[derive(Clone, Default, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct ProfilesWindow {
+    pub new_profile: String,
+    pub status_label: String,
+    pub profiles_window_visible: bool,
+}
+
+impl ProfilesWindow {
+    // Returns the profile picked to switch to, switching can need the API key window for a passphrase
+    pub fn profiles_window(&mut self, parser: &mut Parser, ctx: &egui::Context) -> Option<String> {
+        let mut switch_to = None;
+        let mut visible = self.profiles_window_visible;
+        egui::Window::new("Key Profiles").open(&mut visible).show(ctx, |ui| {
+            if parser.is_key_overridden() {
+                ui.colored_label(Color32::from_rgb(255, 140, 0), "This run uses the key from --key or SPACEPIX_API_KEY.");
+            }
+            let names: Vec<String> = parser.profiles().names().map(String::from).collect();
+            egui::Grid::new("profiles_grid").striped(true).show(ui, |ui| {
+                ui.label(RichText::new("Profile").strong());
+                ui.label(RichText::new("Requests").strong());
+                ui.label(RichText::new("Remaining").strong());
+                ui.end_row();
+
+                let rows = names.iter().map(String::as_str).chain(parser.is_key_overridden().then_some(Parser::OVERRIDE_PROFILE));
+                for name in rows.collect::<Vec<&str>>() {
+                    let in_use = match parser.is_key_overridden() {
+                        true => name == Parser::OVERRIDE_PROFILE,
+                        false => name == parser.profile(),
+                    };
+                    let label = match name == parser.profiles().default_profile() {
+                        true => format!("{} (default)", name),
+                        false => name.to_string(),
+                    };
+                    if ui.selectable_label(in_use, label).clicked() && name != Parser::OVERRIDE_PROFILE {
+                        switch_to = Some(name.to_string());
+                    }
+                    let rate_limit = parser.rate_limit(name);
+                    ui.label(rate_limit.requests.to_string());
+                    ui.label(match (rate_limit.remaining, rate_limit.limit) {
+                        (Some(remaining), Some(limit)) => format!("{} of {}", remaining, limit),
+                        (Some(remaining), None) => remaining.to_string(),
+                        _ => String::from("-"),
+                    });
+                    if name != Parser::OVERRIDE_PROFILE {
+                        ui.horizontal(|ui| {
+                            if ui.button("Make Default").clicked() {
+                                self.status_label = match parser.set_default_profile(name) {
+                                    Ok(_) => format!("Spacepix will start with {}.", name),
+                                    Err(e) => e.to_string(),
+                                };
+                            }
+                            let remove = match name == KeyProfiles::DEFAULT_PROFILE {
+                                true => "Forget Key",
+                                false => "Remove",
+                            };
+                            if ui.button(remove).clicked() {
+                                self.status_label = match parser.remove_profile(name) {
+                                    Ok(_) => format!("Removed the key saved for {}.", name),
+                                    Err(e) => e.to_string(),
+                                };
+                            }
+                        });
+                    }
+                    ui.end_row();
+                }
+            });
+            ui.separator();
+            ui.horizontal(|ui| {
+                ui.text_edit_singleline(&mut self.new_profile);
+                if ui.button("Add Profile").clicked() {
+                    let name = self.new_profile.trim().to_string();
+                    self.status_label = match parser.add_profile(&name) {
+                        Ok(_) => {
+                            self.new_profile = String::default();
+                            switch_to = Some(name);
+                            String::default()
+                        }
+                        Err(e) => e.to_string(),
+                    };
+                }
+            });
+            ui.label(&self.status_label);
+        });
+        self.profiles_window_visible = visible && self.profiles_window_visible;
+        switch_to
+    }
+}
+
+/// Settings->Network, timeouts and retries for the shared HTTP client
+#[derive(Clone, Default, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct NetworkWindow {
+    pub settings: HttpSettings,
+    #[serde(skip)]
+    pub proxy_override: ProxySettings, // From the command line, wins over the saved proxy settings
+    #[serde(skip)]
+    pub new_certificate: String,
+    #[serde(skip)]
+    pub status_label: String,
+    pub network_window_visible: bool,
+}
+
+impl NetworkWindow {
+    // What the shared client is built from, the saved settings with the command line on top
+    pub fn effective_settings(&self) -> HttpSettings {
+        HttpSettings {
+            proxy: self.settings.proxy.overridden_by(&self.proxy_override),
+            ..self.settings.clone()
+        }
+    }
+
+    pub fn network_window(&mut self, ctx: &egui::Context) {
+        let mut visible = self.network_window_visible;
+        egui::Window::new("Network").open(&mut visible).show(ctx, |ui| {
+            egui::Grid::new("network_grid").num_columns(2).show(ui, |ui| {
+                ui.label("Connect timeout");
+                ui.add(egui::DragValue::new(&mut self.settings.connect_timeout_ms).range(100..=120_000).suffix(" ms"));
+                ui.end_row();
+                ui.label("Read timeout");
+                ui.add(egui::DragValue::new(&mut self.settings.read_timeout_ms).range(100..=600_000).suffix(" ms"));
+                ui.end_row();
+                ui.label("Total time per request");
+                ui.add(egui::DragValue::new(&mut self.settings.total_timeout_ms).range(100..=600_000).suffix(" ms"));
+                ui.end_row();
+                ui.label("Retries");
+                ui.add(egui::DragValue::new(&mut self.settings.max_retries).range(0..=10));
+                ui.end_row();
+                ui.label("First backoff");
+                ui.add(egui::DragValue::new(&mut self.settings.initial_backoff_ms).range(0..=60_000).suffix(" ms"));
+                ui.end_row();
+                ui.label("Longest backoff");
+                ui.add(egui::DragValue::new(&mut self.settings.max_backoff_ms).range(0..=300_000).suffix(" ms"));
+                ui.end_row();
+            });
+            ui.label("Server errors, timeouts and dropped connections are retried, 4xx errors never are.");
+            ui.separator();
+            if !self.proxy_override.is_empty() {
+                ui.colored_label(Color32::from_rgb(255, 140, 0), "This run uses proxy flags from the command line.");
+            }
+            let proxy = &mut self.settings.proxy;
+            egui::Grid::new("proxy_grid").num_columns(2).show(ui, |ui| {
+                ui.label("HTTP proxy");
+                ui.text_edit_singleline(&mut proxy.http_proxy);
+                ui.end_row();
+                ui.label("HTTPS proxy");
+                ui.text_edit_singleline(&mut proxy.https_proxy);
+                ui.end_row();
+                ui.label("SOCKS proxy");
+                ui.add(egui::TextEdit::singleline(&mut proxy.socks_proxy).hint_text("socks5h://host:1080"));
+                ui.end_row();
+                ui.label("No proxy");
+                ui.add(egui::TextEdit::singleline(&mut proxy.no_proxy).hint_text("localhost, .lab.internal"));
+                ui.end_row();
+            });
+            ui.label("Extra root certificates (PEM or DER)");
+            let mut remove = None;
+            for (i, path) in proxy.ca_certificates.iter().enumerate() {
+                ui.horizontal(|ui| {
+                    ui.label(path.display().to_string());
+                    if ui.small_button("Remove").clicked() {
+                        remove = Some(i);
+                    }
+                });
+            }
+            if let Some(i) = remove {
+                proxy.ca_certificates.remove(i);
+            }
+            ui.horizontal(|ui| {
+                ui.text_edit_singleline(&mut self.new_certificate);
+                if ui.button("Add Certificate").clicked() && !self.new_certificate.trim().is_empty() {
+                    proxy.ca_certificates.push(PathBuf::from(self.new_certificate.trim()));
+                    self.new_certificate = String::default();
+                }
+            });
+            ui.horizontal(|ui| {
+                if ui.button("Apply").clicked() {
+                    self.status_label = match configure_http_client(&self.effective_settings()) {
+                        Ok(_) => String::from("Applied."),
+                        Err(e) => e.to_string(),
+                    };
+                }
+                if ui.button("Reset to Defaults").clicked() {
+                    self.settings = HttpSettings::default();
+                    self.status_label = match configure_http_client(&self.effective_settings()) {
+                        Ok(_) => String::from("Back to the defaults."),
+                        Err(e) => e.to_string(),
+                    };
+                }
+            });
+            ui.label(&self.status_label);
+        });
+        self.network_window_visible = visible && self.network_window_visible;
+    }
+}
+
+/// Help->Network Inspector, the requests made this run and the raw text that came back
+#[derive(Clone, Default, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct NetworkInspectorWindow {
+    pub filter: String,
+    #[serde(skip)]
+    pub selected: Option<u64>,
+    #[serde(skip)]
+    pub selected_text: String, // The selected response, pretty printed once rather than every frame
+    pub inspector_window_visible: bool,
+}
+
+impl NetworkInspectorWindow {
+    pub fn inspector_window(&mut self, ctx: &egui::Context) {
+        if !self.inspector_window_visible {
+            return;
+        }
+        let records: Vec<RequestRecord> = request_log()
+            .into_iter()
+            .rev()
+            .filter(|record| record.url.contains(self.filter.trim()))
+            .collect();
+        let mut visible = self.inspector_window_visible;
+        egui::Window::new("Network Inspector")
+            .open(&mut visible)
+            .default_width(720.0)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label("Filter");
+                    ui.text_edit_singleline(&mut self.filter);
+                    if ui.button("Clear").clicked() {
+                        clear_request_log();
+                        self.selected = None;
+                    }
+                    ui.label(format!("{} requests", records.len()));
+                });
+                ui.separator();
+                egui::ScrollArea::vertical()
+                    .id_salt("inspector_requests")
+                    .max_height(240.0)
+                    .show(ui, |ui| {
+                        egui::Grid::new("inspector_grid").striped(true).show(ui, |ui| {
+                            for heading in ["Time", "Status", "Latency", "Bytes", "Cache", "Remaining", "URL"] {
+                                ui.label(RichText::new(heading).strong());
+                            }
+                            ui.end_row();
+                            for record in &records {
+                                ui.label(record.time.format("%H:%M:%S").to_string());
+                                match (record.status, record.retries) {
+                                    (Some(status), 0) => ui.label(status.to_string()),
+                                    (Some(status), retries) => ui.label(format!("{} ({} retries)", status, retries)),
+                                    (None, _) => ui.colored_label(Color32::RED, "failed"),
+                                };
+                                ui.label(format!("{} ms", record.latency.as_millis()));
+                                ui.label(record.bytes.map_or(String::from("-"), |bytes| bytes.to_string()));
+                                ui.label(match record.cache {
+                                    Some(true) => "hit",
+                                    Some(false) => "miss",
+                                    None => "-",
+                                });
+                                ui.label(record.remaining.map_or(String::from("-"), |remaining| remaining.to_string()));
+                                if ui.selectable_label(self.selected == Some(record.id), &record.url).clicked() {
+                                    self.selected = Some(record.id);
+                                    // Pretty print JSON so odd fields are easy to spot, anything else as it came
+                                    self.selected_text = match record.body.as_deref().map(json::parse) {
+                                        Some(Ok(json)) => json.pretty(2),
+                                        _ => record.body.as_deref().unwrap_or_default().to_string(),
+                                    };
+                                }
+                                ui.end_row();
+                            }
+                        });
+                    });
+                let Some(record) = records.iter().find(|record| Some(record.id) == self.selected) else {
+                    return;
+                };
+                ui.separator();
+                ui.label(RichText::new(&record.url).strong());
+                if let Some(error) = &record.error {
+                    ui.colored_label(Color32::RED, error);
+                }
+                match record.body {
+                    Some(_) => {
+                        if ui.button("Copy").clicked() {
+                            ctx.copy_text(self.selected_text.clone());
+                        }
+                        egui::ScrollArea::vertical().id_salt("inspector_body").show(ui, |ui| {
+                            ui.add(
+                                egui::TextEdit::multiline(&mut self.selected_text.as_str())
+                                    .code_editor()
+                                    .desired_width(f32::INFINITY),
+                            );
+                        });
+                    }
+                    None => {
+                        ui.label("No text was kept for this response.");
+                    }
+                }
+            });
+        self.inspector_window_visible = visible && self.inspector_window_visible;
+    }
+}
+
+/// Images from api.nasa.gov fetched through the shared client. egui's own loader would keep the
+/// keyed url in its cache and error messages, so it only gets the bytes under a URI without the key
+#[derive(Clone, Default)]
+pub struct KeyedImages {
+    loaded: HashSet<String>,
+    failed: HashSet<String>,
+}
+
+impl KeyedImages {
+    // Fetch the image the first time it's asked for, the error only comes back from that try
+    pub fn fetch(
+        &mut self,
+        ctx: &egui::Context,
+        uri: &str,
+        fetch: impl FnOnce() -> Result<Vec<u8>, SpacepixError>,
+    ) -> Result<(), SpacepixError> {
+        if self.is_fetched(uri) {
+            return Ok(());
+        }
+        match fetch() {
+            Ok(bytes) => {
+                ctx.include_bytes(uri.to_string(), bytes);
+                self.loaded.insert(uri.to_string());
+                Ok(())
+            }
+            Err(e) => {
+                self.failed.insert(uri.to_string());
+                Err(e)
+            }
+        }
+    }
+
+    pub fn is_fetched(&self, uri: &str) -> bool {
+        self.loaded.contains(uri) || self.failed.contains(uri)
+    }
+
+    // Let images that failed be tried again
+    pub fn clear_failed(&mut self) {
+        self.failed.clear();
+    }
+
+    // The image, or a placeholder of the same size when it isn't fetched
+    pub fn show(&self, ui: &mut egui::Ui, uri: &str, size: egui::Vec2) {
+        if self.loaded.contains(uri) {
+            ui.add(Image::from_uri(uri.to_string()).fit_to_exact_size(size));
+            return;
+        }
+        let (rect, _) = ui.allocate_exact_size(size, egui::Sense::hover());
+        ui.painter().rect_filled(rect, 0.0, ui.visuals().extreme_bg_color);
+        let text = match self.failed.contains(uri) {
+            true => "Image unavailable",
+            false => "Image not loaded",
+        };
+        ui.put(rect, egui::Label::new(text));
+    }
+}
+
+/// What failed, so the error panel can say where and its Retry button can repeat it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ErrorSource {
+    Apod,
+    NeoWs,
+    Cad,
+    Nivl,
+    NivlAsset,
+    Captions,
+    WsaEnlil,
+    MarsManifest,
+    MarsPhotos,
+    Epic,
+    EonetFilters,
+    Eonet,
+    Sentry,
+    SentryRecord,
+    Fireballs,
+    Exoplanets,
+    Landsat,
+    TechTransfer,
+    Tle,
+    TlePinned,
+    Download,
+}
+
+impl ErrorSource {
+    // Whether the window can repeat the request by itself, the rest come from clicking on a result again
+    pub fn can_retry(&self) -> bool {
+        !matches!(self, Self::NivlAsset | Self::Captions | Self::SentryRecord | Self::Download)
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Apod => "APOD",
+            Self::NeoWs => "NeoWs",
+            Self::Cad => "JPL CAD",
+            Self::Nivl => "Image and Video Library",
+            Self::NivlAsset => "Image and Video Library asset",
+            Self::Captions => "Captions",
+            Self::WsaEnlil => "WSA-Enlil",
+            Self::MarsManifest => "Mars rover manifest",
+            Self::MarsPhotos => "Mars rover photos",
+            Self::Epic => "EPIC",
+            Self::EonetFilters => "EONET categories",
+            Self::Eonet => "EONET",
+            Self::Sentry => "Sentry risk list",
+            Self::SentryRecord => "Sentry object",
+            Self::Fireballs => "Fireballs",
+            Self::Exoplanets => "Exoplanet Archive",
+            Self::Landsat => "Landsat",
+            Self::TechTransfer => "TechTransfer",
+            Self::Tle => "TLE search",
+            Self::TlePinned => "Pinned satellites",
+            Self::Download => "Download",
+        }
+    }
+}
+
+/// One failure kept in the error panel's history
+#[derive(Debug, Clone)]
+pub struct ErrorEntry {
+    pub source: ErrorSource,
+    pub message: String,
+    pub details: Vec<String>, // The message and its source chain
+    pub time: chrono::DateTime<chrono::Local>,
+    pub retryable: bool,
+}
+
+/// A strip along the bottom of the window showing the latest failure, with a history of recent ones
+#[derive(Clone, Default, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct ErrorPanel {
+    #[serde(skip)]
+    pub history: VecDeque<ErrorEntry>, // Newest first
+    #[serde(skip)]
+    pub latest_visible: bool,
+    #[serde(skip)]
+    retry: Option<ErrorSource>,
+    #[serde(skip)]
+    polled: HashSet<ErrorSource>, // Whose windows asked for a retry last frame, only they can take one
+    #[serde(skip)]
+    polling: HashSet<ErrorSource>,
+    pub history_window_visible: bool,
+}
+
+impl ErrorPanel {
+    const HISTORY_LEN: usize = 50;
+
+    // Keep the error and show it in the panel, returns the message for the window's own label
+    pub fn report(&mut self, source: ErrorSource, e: &SpacepixError) -> String {
+        let details: Vec<String> = e.chain().iter().map(|line| redact_api_key(line)).collect();
+        tracing::warn!("{} failed: {}", source.name(), details.join(": "));
+        self.history.push_front(ErrorEntry {
+            source,
+            message: e.to_string(),
+            details,
+            time: chrono::Local::now(),
+            retryable: e.is_retryable() && source.can_retry(),
+        });
+        self.history.truncate(Self::HISTORY_LEN);
+        self.latest_visible = true;
+        e.to_string()
+    }
+
+    // True once in the frame Retry was clicked for this source, the window then repeats its request.
+    // The strip stays up until then, a failed retry puts it back through report()
+    pub fn take_retry(&mut self, source: ErrorSource) -> bool {
+        self.polling.insert(source);
+        if self.retry != Some(source) {
+            return false;
+        }
+        self.retry = None;
+        if self.history.front().is_some_and(|latest| latest.source == source) {
+            self.latest_visible = false;
+        }
+        true
+    }
+
+    pub fn error_panel(&mut self, ctx: &egui::Context) {
+        // The windows drawn after the panel had their one frame to take the retry, a window that was
+        // closed or collapsed mustn't find it waiting when it's opened again
+        self.retry = None;
+        // A closed or collapsed window never asks, so Retry stays greyed out until it's open again
+        self.polled = std::mem::take(&mut self.polling);
+        if let Some(latest) = self.history.front().filter(|_| self.latest_visible).cloned() {
+            egui::TopBottomPanel::bottom("error_panel").show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.colored_label(Color32::from_rgb(255, 80, 80), format!("{}: {}", latest.source.name(), latest.message))
+                        .on_hover_text(latest.details.join("\n"));
+                    if latest.retryable
+                        && ui
+                            .add_enabled(self.polled.contains(&latest.source), egui::Button::new("Retry"))
+                            .on_disabled_hover_text(format!("Open the {} window to retry", latest.source.name()))
+                            .clicked()
+                    {
+                        self.retry = Some(latest.source);
+                    }
+                    if ui.button("History").clicked() {
+                        self.history_window_visible = true;
+                    }
+                    if ui.button("Dismiss").clicked() {
+                        self.latest_visible = false;
+                    }
+                });
+            });
+        }
+
+        let mut visible = self.history_window_visible;
+        let polled = self.polled.clone();
+        egui::Window::new("Recent Errors").open(&mut visible).show(ctx, |ui| {
+            if self.history.is_empty() {
+                ui.label("Nothing has failed yet.");
+            }
+            if !self.history.is_empty() && ui.button("Clear").clicked() {
+                self.history.clear();
+                self.latest_visible = false;
+            }
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                for (i, entry) in self.history.iter().enumerate() {
+                    ui.horizontal(|ui| {
+                        ui.label(entry.time.format("%H:%M:%S").to_string());
+                        ui.label(RichText::new(entry.source.name()).strong());
+                        if entry.retryable
+                            && ui
+                                .add_enabled(polled.contains(&entry.source), egui::Button::new("Retry").small())
+                                .on_disabled_hover_text(format!("Open the {} window to retry", entry.source.name()))
+                                .clicked()
+                        {
+                            self.retry = Some(entry.source);
+                        }
+                    });
+                    egui::CollapsingHeader::new(&entry.message).id_salt(("error", i)).show(ui, |ui| {
+                        for (depth, detail) in entry.details.iter().enumerate().skip(1) {
+                            ui.label(format!("{}caused by: {}", "  ".repeat(depth - 1), detail));
+                        }
+                    });
+                    ui.separator();
+                }
+            });
+        });
+        self.history_window_visible = visible;
+    }
+}
@@ -6,12 +6,57 @@ use std::usize;
 const APOD: &str = "https://api.nasa.gov/planetary/apod?api_key=";
 const NEOWS: &str = "https://api.nasa.gov/neo/rest/v1/feed?start_date=START_DATE&end_date=END_DATE&api_key=";
 const DONKI: &str = "https://api.nasa.gov/DONKI/CME?startDate=yyyy-MM-dd&endDate=yyyy-MM-dd&api_key=";
+const NIVL_SEARCH: &str = "https://images-api.nasa.gov/search";
+const NIVL_ASSET: &str = "https://images-api.nasa.gov/asset";
+const NIVL_METADATA: &str = "https://images-api.nasa.gov/metadata";
+const NIVL_ALBUM: &str = "https://images-api.nasa.gov/album";
+const WSA_ENLIL: &str = "https://api.nasa.gov/DONKI/WSAEnlilSimulations?startDate=START_DATE&endDate=END_DATE&api_key=";
+const MARS_PHOTOS: &str = "https://api.nasa.gov/mars-photos/api/v1/rovers/ROVER/photos?QUERY&api_key=";
+const MARS_MANIFEST: &str = "https://api.nasa.gov/mars-photos/api/v1/manifests/ROVER?api_key=";
+const EPIC: &str = "https://api.nasa.gov/EPIC/api/COLLECTION/DATE?api_key=";
+const EPIC_ARCHIVE: &str = "https://api.nasa.gov/EPIC/archive/COLLECTION/DATE_PATH/FORMAT/IMAGE.EXTENSION?api_key=";
+const EONET_EVENTS: &str = "https://eonet.gsfc.nasa.gov/api/v3/events";
+const EONET_CATEGORIES: &str = "https://eonet.gsfc.nasa.gov/api/v3/categories";
+const EONET_SOURCES: &str = "https://eonet.gsfc.nasa.gov/api/v3/sources";
+const SENTRY: &str = "https://ssd-api.jpl.nasa.gov/sentry.api";
+const FIREBALL: &str = "https://ssd-api.jpl.nasa.gov/fireball.api";
+const CAD: &str = "https://ssd-api.jpl.nasa.gov/cad.api";
+const EXOPLANET_TAP: &str = "https://exoplanetarchive.ipac.caltech.edu/TAP/sync";
+const EARTH_ASSETS: &str = "https://api.nasa.gov/planetary/earth/assets?QUERY&api_key=";
+const EARTH_IMAGERY: &str = "https://api.nasa.gov/planetary/earth/imagery?QUERY&api_key=";
+const TECHTRANSFER: &str = "https://api.nasa.gov/techtransfer/CATEGORY/?QUERY&api_key=";
+const TLE_SEARCH: &str = "https://tle.ivanstanojevic.me/api/tle";
+const TLE_RECORD: &str = "https://tle.ivanstanojevic.me/api/tle/SATELLITE_ID";
+const KEY_CHECK: &str = "https://api.nasa.gov/neo/rest/v1/stats?api_key=";
 
 #[derive(Clone, Debug, serde::Deserialize, serde::Serialize)]
+#[serde(default)] // if we add new fields, give them default values when deserializing old state
 pub struct Urls {
     pub apod: String,
     pub neows: String,
     pub donki: String,
+    pub wsa_enlil: String,
+    pub nivl_search: String,
+    pub nivl_asset: String,
+    pub nivl_metadata: String,
+    pub nivl_album: String,
+    pub mars_photos: String,
+    pub mars_manifest: String,
+    pub epic: String,
+    pub epic_archive: String,
+    pub eonet_events: String,
+    pub eonet_categories: String,
+    pub eonet_sources: String,
+    pub sentry: String,
+    pub fireball: String,
+    pub cad: String,
+    pub exoplanet_tap: String,
+    pub earth_assets: String,
+    pub earth_imagery: String,
+    pub techtransfer: String,
+    pub tle_search: String,
+    pub tle_record: String,
+    pub key_check: String,
 }
 
 impl Default for Urls {
@@ -19,7 +64,29 @@ impl Default for Urls {
         Self {
             apod: String::from(APOD),
             neows: String::from(NEOWS),
-            donki: String::from(DONKI)
+            donki: String::from(DONKI),
+            wsa_enlil: String::from(WSA_ENLIL),
+            nivl_search: String::from(NIVL_SEARCH),
+            nivl_asset: String::from(NIVL_ASSET),
+            nivl_metadata: String::from(NIVL_METADATA),
+            nivl_album: String::from(NIVL_ALBUM),
+            mars_photos: String::from(MARS_PHOTOS),
+            mars_manifest: String::from(MARS_MANIFEST),
+            epic: String::from(EPIC),
+            epic_archive: String::from(EPIC_ARCHIVE),
+            eonet_events: String::from(EONET_EVENTS),
+            eonet_categories: String::from(EONET_CATEGORIES),
+            eonet_sources: String::from(EONET_SOURCES),
+            sentry: String::from(SENTRY),
+            fireball: String::from(FIREBALL),
+            cad: String::from(CAD),
+            exoplanet_tap: String::from(EXOPLANET_TAP),
+            earth_assets: String::from(EARTH_ASSETS),
+            earth_imagery: String::from(EARTH_IMAGERY),
+            techtransfer: String::from(TECHTRANSFER),
+            tle_search: String::from(TLE_SEARCH),
+            tle_record: String::from(TLE_RECORD),
+            key_check: String::from(KEY_CHECK),
         }
     }
 }